@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     ops::Deref,
     sync::{
         Arc,
@@ -8,11 +9,10 @@ use std::{
 };
 
 use harmonica::spring::Spring;
-use num_enum::FromPrimitive;
-use rand::{Rng, TryRngCore, rand_core::UnwrapErr, rngs::OsRng, seq::SliceRandom};
+use rand::{Rng, TryRngCore, rand_core::UnwrapErr, rngs::OsRng};
 use web_time::Instant;
 
-use arc_swap::ArcSwap;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use bebop::Record;
 use egui::{
     Align2, Color32, CornerRadius, FontId, Frame, Pos2, Rect, RichText, Sense, Vec2,
@@ -21,12 +21,30 @@ use egui::{
 use futures_util::{SinkExt, StreamExt};
 use indexmap::IndexMap;
 use keyframe::functions;
-use web_sys::wasm_bindgen::JsCast;
 use ws_stream_wasm::{WsMessage, WsMeta};
 
-use crate::messages::redis_game::{GameMessage, KeyValue};
+use crate::messages::redis_game::{
+    Click, ErrorCode, ErrorMessage, GameMessage, Hello, PROTOCOL_VERSION, PlayerJoined, PlayerLeft,
+    PowerupKind, Role, RoundPhase, RoundState, Rules, Welcome,
+};
 
 const SPRING_TARGET_X: f32 = 5.0;
+/// How long the slow down notice stays up after the server drops a click
+const THROTTLE_NOTICE: Duration = Duration::from_secs(1);
+/// Shortest and longest wait before reconnecting after the connection drops
+const RECONNECT_MIN_BACKOFF: Duration = Duration::from_millis(500);
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+/// Optional features we can handle, the server says which ones it agreed to
+const CAPABILITIES: [&str; 2] = ["resume", "powerups"];
+/// Height of the bar showing how much of the round is left, hosts get a bigger one
+const ROUND_BAR_HEIGHT: f32 = 24.0;
+const HOST_ROUND_BAR_HEIGHT: f32 = 48.0;
+/// Colors of the podium steps, from first place down
+const PODIUM_COLORS: [Color32; 3] = [
+    Color32::from_rgb(212, 175, 55),
+    Color32::from_rgb(192, 192, 192),
+    Color32::from_rgb(205, 127, 50),
+];
 
 struct CellAnimation {
     x_spring: Spring,
@@ -34,18 +52,17 @@ struct CellAnimation {
 }
 
 impl CellAnimation {
+    /// New cells start below their resting place and spring up into it
     fn new() -> Self {
         Self {
             x_spring: Spring::new(1.0 / 60.0, 6.0, 0.5, 0.0, SPRING_TARGET_X),
-            y_spring: Spring::new(1.0 / 60.0, 6.0, 0.5, 0.0, 0.0),
+            y_spring: Spring::new(1.0 / 60.0, 6.0, 0.5, 1.0, 0.0),
         }
     }
 }
 
-#[derive(FromPrimitive, Default, Clone, Copy, PartialEq, Eq)]
-#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum Powerup {
-    #[default]
     Rows,
     Columns,
     RowsColumns,
@@ -53,13 +70,97 @@ enum Powerup {
     Autoclick,
 }
 
-const POWERUPS: [Powerup; 5] = [
-    Powerup::Rows,
-    Powerup::Columns,
-    Powerup::RowsColumns,
-    Powerup::Random,
-    Powerup::Autoclick,
-];
+impl From<PowerupKind> for Powerup {
+    fn from(kind: PowerupKind) -> Self {
+        match kind {
+            PowerupKind::Rows => Self::Rows,
+            PowerupKind::Columns => Self::Columns,
+            PowerupKind::RowsColumns => Self::RowsColumns,
+            PowerupKind::Random => Self::Random,
+            PowerupKind::Autoclick => Self::Autoclick,
+        }
+    }
+}
+
+/// The numbers the server plays the game with
+#[derive(Clone, Copy)]
+struct GameRules {
+    /// Points a click adds to or takes from a player, to predict scores with
+    click_points: i64,
+    /// How often the server sends score changes, autoclicks go no faster
+    tick: Duration,
+    /// Whether clicks only count while a round is played
+    rounds: bool,
+    /// How long a powerup stays active
+    powerup: Duration,
+    /// How long after a powerup ends until the next one can be activated
+    powerup_cooldown: Duration,
+}
+
+impl Default for GameRules {
+    /// What the server plays with unless it's configured otherwise
+    fn default() -> Self {
+        Self {
+            click_points: 10_000,
+            tick: Duration::from_millis(50),
+            rounds: true,
+            powerup: Duration::from_secs(8),
+            powerup_cooldown: Duration::from_secs(10),
+        }
+    }
+}
+
+impl From<Rules> for GameRules {
+    fn from(rules: Rules) -> Self {
+        let default = Self::default();
+        Self {
+            click_points: rules.click_points.unwrap_or(default.click_points),
+            tick: rules
+                .tick_ms
+                .map_or(default.tick, |ms| Duration::from_millis(ms.into())),
+            rounds: rules.rounds.unwrap_or(default.rounds),
+            powerup: rules
+                .powerup_ms
+                .map_or(default.powerup, |ms| Duration::from_millis(ms.into())),
+            powerup_cooldown: rules
+                .powerup_cooldown_ms
+                .map_or(default.powerup_cooldown, |ms| {
+                    Duration::from_millis(ms.into())
+                }),
+        }
+    }
+}
+
+/// What the game sends to the server
+enum Outgoing {
+    /// The clicked player ids and whether each click was an assist
+    Clicks(Vec<(u32, bool)>),
+    ActivatePowerup,
+}
+
+/// What the server told us that only this player needs to know
+enum ServerEvent {
+    /// The server let us in, hosts show the leaderboard instead of the grid
+    Welcome {
+        host: bool,
+        player_id: Option<u32>,
+        rules: GameRules,
+        /// The server agreed to powerups
+        powerups: bool,
+    },
+    /// The connection dropped, we're trying to get back in
+    Disconnected,
+    /// The server turned us away, the join window says why
+    Rejected,
+    /// Some of our clicks were dropped for coming in too fast
+    Throttled,
+    Powerup {
+        /// Not set when the last powerup is still cooling down
+        kind: Option<Powerup>,
+        active_for: Duration,
+        ready_in: Duration,
+    },
+}
 
 impl Powerup {
     fn hovered(&self, cursor_pos: (usize, usize), grid_cell: (usize, usize)) -> bool {
@@ -75,22 +176,135 @@ impl Powerup {
     }
 }
 
+/// A cell of the grid, keyed by the player id the server gave them
+#[derive(Debug)]
+struct Person {
+    name: String,
+    score: AtomicI64,
+}
+
+/// A row of the leaderboard as ranked by the server
+struct Ranked {
+    rank: u32,
+    name: String,
+    score: i64,
+}
+
+/// Where the room's round is at, as last told by the server
+struct Round {
+    phase: RoundPhase,
+    number: u32,
+    /// When the phase ends, the lobby waits for enough players instead
+    ends: Option<Instant>,
+    /// How long the phase lasts in all
+    duration: Duration,
+    min_players: u32,
+    /// The round's best players, only sent with the results
+    podium: Vec<Ranked>,
+}
+
+impl From<&RoundState<'_>> for Round {
+    fn from(round: &RoundState<'_>) -> Self {
+        Self {
+            phase: round.phase.unwrap_or(RoundPhase::Lobby),
+            number: round.number.unwrap_or_default(),
+            ends: round
+                .remaining_ms
+                .map(|ms| Instant::now() + Duration::from_millis(ms.into())),
+            duration: Duration::from_millis(round.duration_ms.unwrap_or_default().into()),
+            min_players: round.min_players.unwrap_or_default(),
+            podium: round
+                .podium
+                .iter()
+                .flatten()
+                .map(|ranked| Ranked {
+                    rank: ranked.rank,
+                    name: ranked.key.to_owned(),
+                    score: ranked.value,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Round {
+    fn remaining(&self) -> Duration {
+        self.ends.map_or(Duration::ZERO, |ends| {
+            ends.saturating_duration_since(Instant::now())
+        })
+    }
+
+    /// How much of the phase is left, from 1 when it starts down to 0 when it ends
+    fn left(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (self.remaining().as_secs_f32() / self.duration.as_secs_f32()).clamp(0.0, 1.0)
+    }
+
+    fn describe(&self) -> String {
+        let seconds = self.remaining().as_secs_f32().ceil() as u64;
+        match self.phase {
+            RoundPhase::Lobby => {
+                format!("Waiting for {} players to start a round", self.min_players)
+            }
+            RoundPhase::Countdown => format!("Round {} starts in {}s", self.number, seconds),
+            RoundPhase::Playing => format!(
+                "Round {}: {}:{:02} left",
+                self.number,
+                seconds / 60,
+                seconds % 60
+            ),
+            RoundPhase::Results => format!("Next round in {seconds}s"),
+        }
+    }
+
+    fn color(&self) -> Color32 {
+        match self.phase {
+            RoundPhase::Lobby => Color32::DARK_GRAY,
+            RoundPhase::Countdown => Color32::from_rgb(200, 140, 0),
+            RoundPhase::Playing => Color32::from_rgb(40, 140, 60),
+            RoundPhase::Results => Color32::from_rgb(60, 90, 180),
+        }
+    }
+}
+
 pub struct TemplateApp {
     label: String,
+    /// Finds our own cell, the server may have normalised the name we typed
+    player_id: Option<u32>,
     joined: Arc<AtomicBool>,
     leaderboard: bool,
     error: Arc<ArcSwap<String>>,
-    people: Arc<ArcSwap<IndexMap<String, AtomicI64>>>,
-    click_sender: flume::Sender<Vec<(String, i64)>>,
-    animation_state: Vec<CellAnimation>,
+    people: Arc<ArcSwap<IndexMap<u32, Person>>>,
+    ranking: Arc<ArcSwap<Vec<Ranked>>>,
+    /// Not known until the server first says where the round is at
+    round: Arc<ArcSwapOption<Round>>,
+    /// When the server last dropped one of our clicks for coming in too fast
+    throttled: Option<Instant>,
+    /// The connection dropped and hasn't been resumed yet
+    reconnecting: bool,
+    outgoing: flume::Sender<Outgoing>,
+    server_events: flume::Receiver<ServerEvent>,
+    /// Keyed by player id so a join or leave doesn't reset everyone else's springs
+    animation_state: HashMap<u32, CellAnimation>,
+    /// When the active powerup runs out
     powerup_instant: Instant,
+    /// When the server will grant the next powerup
+    powerup_ready: Instant,
+    /// How long the bar takes to fill up again after a powerup runs out
+    powerup_cooldown: Duration,
+    /// Waiting on the server to answer an activation
+    powerup_requested: bool,
     autoclick_instant: Instant,
     show_powerup_window: bool,
     rng: UnwrapErr<OsRng>,
     x_down: bool,
     z_down: bool,
     powerup: Option<Powerup>,
-    powerups: std::array::IntoIter<Powerup, 5>,
+    rules: GameRules,
+    /// The server agreed to powerups in the handshake
+    powerups: bool,
 }
 
 impl Default for TemplateApp {
@@ -98,24 +312,31 @@ impl Default for TemplateApp {
         Self {
             // Example stuff:
             label: String::new(),
+            player_id: None,
             joined: Arc::new(false.into()),
             leaderboard: false,
             error: Arc::new(ArcSwap::new(Arc::new(String::new()))),
             people: Arc::new(ArcSwap::new(Arc::new(IndexMap::default()))),
-            click_sender: flume::unbounded().0,
-            animation_state: Vec::new(),
+            ranking: Arc::new(ArcSwap::new(Arc::new(Vec::new()))),
+            round: Arc::new(ArcSwapOption::empty()),
+            throttled: None,
+            reconnecting: false,
+            outgoing: flume::unbounded().0,
+            server_events: flume::unbounded().1,
+            animation_state: HashMap::new(),
             powerup_instant: Instant::now(),
+            // The server lets the first powerup be activated straight away
+            powerup_ready: Instant::now(),
+            powerup_cooldown: GameRules::default().powerup_cooldown,
+            powerup_requested: false,
             autoclick_instant: Instant::now(),
             show_powerup_window: true,
             rng: OsRng.unwrap_err(),
             x_down: false,
             z_down: false,
             powerup: None,
-            powerups: {
-                let mut powerups = POWERUPS;
-                powerups.shuffle(&mut OsRng.unwrap_err());
-                powerups.into_iter()
-            },
+            rules: GameRules::default(),
+            powerups: false,
         }
     }
 }
@@ -132,14 +353,54 @@ impl TemplateApp {
         Default::default()
     }
 
-    fn next_powerup(&mut self) -> Powerup {
-        if let Some(powerup) = self.powerups.next() {
-            powerup
-        } else {
-            let mut powerups = POWERUPS;
-            powerups.shuffle(&mut self.rng);
-            self.powerups = powerups.into_iter();
-            self.powerups.next().unwrap()
+    fn handle_server_events(&mut self) {
+        for event in self.server_events.try_iter() {
+            match event {
+                ServerEvent::Welcome {
+                    host,
+                    player_id,
+                    rules,
+                    powerups,
+                } => {
+                    self.player_id = player_id;
+                    self.rules = rules;
+                    self.powerup_cooldown = rules.powerup_cooldown;
+                    self.powerups = powerups;
+                    if !self.reconnecting {
+                        self.show_powerup_window = !host && powerups;
+                    }
+                    self.leaderboard = host;
+                    self.reconnecting = false;
+                    // The server says where the powerup is at right after
+                    self.powerup_requested = false;
+                }
+                ServerEvent::Disconnected => {
+                    self.reconnecting = true;
+                    // The request or its answer may have been lost with the connection
+                    self.powerup_requested = false;
+                }
+                ServerEvent::Rejected => self.reconnecting = false,
+                ServerEvent::Throttled => self.throttled = Some(Instant::now()),
+                ServerEvent::Powerup {
+                    kind,
+                    active_for,
+                    ready_in,
+                } => {
+                    let now = Instant::now();
+                    self.powerup = kind;
+                    self.powerup_ready = now + ready_in;
+                    if ready_in > active_for {
+                        self.powerup_instant = now + active_for;
+                        self.powerup_cooldown = ready_in - active_for;
+                    } else {
+                        // Nothing to wait for, the bar is full
+                        self.powerup_cooldown = self.rules.powerup_cooldown;
+                        self.powerup_instant =
+                            now.checked_sub(self.powerup_cooldown).unwrap_or(now);
+                    }
+                    self.powerup_requested = false;
+                }
+            }
         }
     }
 }
@@ -169,24 +430,44 @@ impl eframe::App for TemplateApp {
         //     });
         // });
 
+        self.handle_server_events();
+
         let joined = self.joined.load(Ordering::Relaxed);
+        let round = self.round.load_full();
+        // Clicks only count while a round is being played, or always in an endless game
+        let playing = !self.rules.rounds
+            || round
+                .as_ref()
+                .is_some_and(|round| round.phase == RoundPhase::Playing);
         if self.leaderboard {
             egui::CentralPanel::default()
                 .frame(Frame::new().fill(Color32::BLACK))
                 .show(ctx, |ui| {
-                    let map = self.people.load();
-                    let mut map = map.iter().collect::<Vec<_>>();
-                    map.sort_by_key(|k| k.1.load(Ordering::Relaxed));
-                    let max_score = map.last().map_or(0, |s| s.1.load(Ordering::Relaxed)) as f32;
-                    let min_score =
-                        map.get(0).map_or(i64::MIN, |s| s.1.load(Ordering::Relaxed)) as f32;
+                    if let Some(round) = &round {
+                        let (rect, _) = ui.allocate_exact_size(
+                            Vec2::new(ui.available_width(), HOST_ROUND_BAR_HEIGHT),
+                            Sense::hover(),
+                        );
+                        round_bar(ui, rect, round);
+                        ui.add_space(8.0);
+                    }
+                    // Already ranked from the highest score down by the server
+                    let ranking = self.ranking.load();
+                    let max_score = ranking.first().map_or(0, |r| r.score) as f32;
+                    let min_score = ranking.last().map_or(i64::MIN, |r| r.score) as f32;
                     egui::Grid::new("leaderboard")
-                        .num_columns(3)
+                        .num_columns(4)
                         .spacing([40.0, 4.0])
                         .show(ui, |ui| {
-                            for (name, score) in map.into_iter().rev() {
-                                ui.label(RichText::new(name).font(FontId::proportional(32.0)));
-                                let score = score.load(Ordering::Relaxed) as f32;
+                            for ranked in ranking.iter() {
+                                ui.label(
+                                    RichText::new(format!("#{}", ranked.rank))
+                                        .font(FontId::proportional(32.0)),
+                                );
+                                ui.label(
+                                    RichText::new(&ranked.name).font(FontId::proportional(32.0)),
+                                );
+                                let score = ranked.score as f32;
                                 ui.label(
                                     RichText::new(format!("{}", score))
                                         .font(FontId::proportional(32.0)),
@@ -206,8 +487,7 @@ impl eframe::App for TemplateApp {
                 .frame(Frame::new().fill(Color32::BLACK))
                 .show(ctx, |ui| {
                     let map = self.people.load();
-                    self.animation_state
-                        .resize_with(map.len(), CellAnimation::new);
+                    self.animation_state.retain(|id, _| map.contains_key(id));
 
                     let grid_stride = if map.len() <= 2 {
                         map.len()
@@ -215,18 +495,24 @@ impl eframe::App for TemplateApp {
                         (map.len() as f64 + (map.len() % 2) as f64).sqrt().ceil() as usize
                     };
 
-                    let max_rect = ui.max_rect();
+                    // The round bar takes the top of the panel and the grid the rest
+                    let panel_rect = ui.max_rect();
+                    let round_rect = panel_rect.with_max_y(panel_rect.min.y + ROUND_BAR_HEIGHT);
+                    let max_rect = panel_rect.with_min_y(round_rect.max.y);
+                    if let Some(round) = &round {
+                        round_bar(ui, round_rect, round);
+                    }
                     let cell_size = Vec2::new(
                         max_rect.width() / grid_stride as f32,
                         max_rect.height() / grid_stride as f32,
                     );
                     let grid_cell_pointer_pos = ui.input(|i| i.pointer.latest_pos()).map(|pos| {
                         (
-                            (pos.x / cell_size.x) as usize,
-                            (pos.y / cell_size.y) as usize,
+                            ((pos.x - max_rect.min.x) / cell_size.x) as usize,
+                            ((pos.y - max_rect.min.y) / cell_size.y) as usize,
                         )
                     });
-                    let mut map_iter = map.iter().zip(self.animation_state.iter_mut());
+                    let mut map_iter = map.iter();
                     let x_currently_down = ui.input(|i| i.key_down(egui::Key::X));
                     let z_currently_down = ui.input(|i| i.key_down(egui::Key::Z));
                     let mut xz_pressed = false;
@@ -275,29 +561,38 @@ impl eframe::App for TemplateApp {
                                 ui.style().visuals.window_stroke,
                                 egui::StrokeKind::Middle,
                             );
-                            if let Some(((name, score), animation_state)) = map_iter.next() {
+                            if let Some((id, Person { name, score })) = map_iter.next() {
+                                let animation_state = self
+                                    .animation_state
+                                    .entry(*id)
+                                    .or_insert_with(CellAnimation::new);
                                 let mut target_y = 0.0;
                                 let mut target_x = 0.0;
-                                if hovered
+                                if playing
+                                    && hovered
                                     && (xz_pressed
                                         || ui.input(|i| i.pointer.any_click())
                                         || (matches!(
                                             self.powerup,
                                             Some(Powerup::Autoclick | Powerup::Random)
-                                        ) && self.autoclick_instant.elapsed().as_millis() > 50))
+                                        ) && self.autoclick_instant.elapsed()
+                                            > self.rules.tick))
                                 {
                                     target_x =
                                         self.rng.random_range(-SPRING_TARGET_X..SPRING_TARGET_X);
                                     target_y = self.rng.random_range(0.0..SPRING_TARGET_X);
-                                    if name == &self.label
-                                        || (ui.input(|i| i.modifiers.ctrl)
-                                            && grid_cell_pointer_pos == Some((x, y)))
+                                    // The server decides the points, this only predicts them
+                                    if Some(*id) == self.player_id {
+                                        clicks.push((*id, false));
+                                        score.fetch_add(self.rules.click_points, Ordering::Relaxed);
+                                    } else if ui.input(|i| i.modifiers.ctrl)
+                                        && grid_cell_pointer_pos == Some((x, y))
                                     {
-                                        clicks.push((name.clone(), 10_000));
-                                        score.fetch_add(10_000, Ordering::Relaxed);
+                                        clicks.push((*id, true));
+                                        score.fetch_add(self.rules.click_points, Ordering::Relaxed);
                                     } else {
-                                        clicks.push((name.clone(), -10_000i64));
-                                        score.fetch_sub(10_000, Ordering::Relaxed);
+                                        clicks.push((*id, false));
+                                        score.fetch_sub(self.rules.click_points, Ordering::Relaxed);
                                     }
                                 }
                                 ui.painter().text(
@@ -335,10 +630,17 @@ impl eframe::App for TemplateApp {
                     }
                     if !clicks.is_empty() {
                         self.autoclick_instant = Instant::now();
-                        self.click_sender.send(clicks).unwrap();
+                        self.outgoing.send(Outgoing::Clicks(clicks)).unwrap();
                     }
                     if joined {
                         let now = Instant::now();
+                        // The bar empties while the powerup is active and fills up while it cools down
+                        let (left, length) = if self.powerup_instant > now {
+                            (self.powerup_instant.duration_since(now), self.rules.powerup)
+                        } else {
+                            self.powerup = None;
+                            (self.powerup_instant.elapsed(), self.powerup_cooldown)
+                        };
                         ui.painter().rect_filled(
                             Rect::from_min_max(
                                 Pos2::new(max_rect.min.x, max_rect.max.y - 12.0),
@@ -347,13 +649,8 @@ impl eframe::App for TemplateApp {
                                         functions::Linear,
                                         max_rect.min.x,
                                         max_rect.max.x,
-                                        if self.powerup_instant > now {
-                                            self.powerup_instant.duration_since(now).as_secs_f64()
-                                        } else {
-                                            self.powerup = None;
-                                            self.powerup_instant.elapsed().as_secs_f64()
-                                        },
-                                        10.0,
+                                        left.as_secs_f64(),
+                                        length.as_secs_f64(),
                                     ),
                                     max_rect.max.y,
                                 ),
@@ -365,9 +662,17 @@ impl eframe::App for TemplateApp {
                 });
         }
 
-        if ctx.input(|i| i.modifiers.shift) && self.powerup_instant.elapsed().as_secs() > 9 {
-            self.powerup_instant = Instant::now() + Duration::from_secs(8);
-            self.powerup = Some(self.next_powerup());
+        // The server picks the powerup and enforces the cooldown, the bar only predicts it
+        if joined
+            && playing
+            && !self.leaderboard
+            && self.powerups
+            && !self.powerup_requested
+            && ctx.input(|i| i.modifiers.shift)
+            && Instant::now() >= self.powerup_ready
+        {
+            self.outgoing.send(Outgoing::ActivatePowerup).unwrap();
+            self.powerup_requested = true;
         }
 
         if !joined {
@@ -380,17 +685,20 @@ impl eframe::App for TemplateApp {
                         || (label_response.lost_focus()
                             && ui.input(|i| i.key_pressed(egui::Key::Enter)))
                     {
-                        let joined = Arc::clone(&self.joined);
-                        let error = Arc::clone(&self.error);
-                        let people = Arc::clone(&self.people);
                         let label = self.label.clone();
                         let (tx, rx) = flume::unbounded();
-                        self.click_sender = tx;
-                        self.leaderboard = self.label.to_lowercase() == "bradshaw";
-                        self.show_powerup_window = !self.leaderboard;
-                        wasm_bindgen_futures::spawn_local(websocket(
-                            joined, error, people, label, rx,
-                        ));
+                        self.outgoing = tx;
+                        let (events, server_events) = flume::unbounded();
+                        self.server_events = server_events;
+                        let shared = Shared {
+                            joined: Arc::clone(&self.joined),
+                            error: Arc::clone(&self.error),
+                            people: Arc::clone(&self.people),
+                            ranking: Arc::clone(&self.ranking),
+                            round: Arc::clone(&self.round),
+                            events,
+                        };
+                        wasm_bindgen_futures::spawn_local(websocket(shared, label, rx));
                     }
                     ui.label(self.error.load().deref().deref())
                 });
@@ -403,31 +711,310 @@ impl eframe::App for TemplateApp {
                     ui.heading("- Click other people's names to make them lose points");
                     ui.heading("- Hold Ctrl and click your friends to help them");
                     ui.heading("- Press shift to use powerups when the bar is full");
+                    if self.rules.rounds {
+                        ui.heading("- Clicks only count while a round is on, the bar at the top shows how long it has left");
+                    }
                 });
             if ctx.input(|i| i.modifiers.shift) {
                 self.show_powerup_window = false;
             }
         }
+        if self
+            .throttled
+            .is_some_and(|throttled| throttled.elapsed() < THROTTLE_NOTICE)
+        {
+            egui::Window::new("Slow down")
+                .auto_sized()
+                .anchor(Align2::CENTER_TOP, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.heading("You're clicking too fast, some clicks were dropped");
+                });
+        }
+        if let Some(round) = round.filter(|round| joined && round.phase == RoundPhase::Results) {
+            egui::Window::new(format!("Round {} results", round.number))
+                .id(egui::Id::new("podium"))
+                .auto_sized()
+                .collapsible(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| podium(ui, &round.podium));
+        }
+        if self.reconnecting {
+            egui::Window::new("Reconnecting")
+                .auto_sized()
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.heading("Lost the connection to the server, trying to get back in…");
+                });
+        }
         ctx.request_repaint();
     }
 }
 
-async fn websocket(
+/// Fills `rect` with how much of the round's phase is left, saying what's going on over it
+fn round_bar(ui: &egui::Ui, rect: Rect, round: &Round) {
+    let painter = ui.painter();
+    painter.rect_filled(rect, CornerRadius::ZERO, ui.style().visuals.window_fill);
+    painter.rect_filled(
+        rect.with_max_x(lerp(rect.min.x..=rect.max.x, round.left())),
+        CornerRadius::ZERO,
+        round.color(),
+    );
+    painter.text(
+        rect.center(),
+        Align2::CENTER_CENTER,
+        round.describe(),
+        FontId::proportional(rect.height() * 0.7),
+        Color32::WHITE,
+    );
+}
+
+/// The round's best players on a podium, the winner in the middle
+fn podium(ui: &mut egui::Ui, podium: &[Ranked]) {
+    if podium.is_empty() {
+        ui.heading("Nobody scored this round");
+        return;
+    }
+    let (rect, _) = ui.allocate_exact_size(Vec2::new(480.0, 260.0), Sense::hover());
+    let painter = ui.painter();
+    let step_width = rect.width() / 3.0;
+    // Second place on the left, first in the middle and third on the right
+    for (column, rank, height, color) in [
+        (0, 2, 0.55, PODIUM_COLORS[1]),
+        (1, 1, 0.75, PODIUM_COLORS[0]),
+        (2, 3, 0.4, PODIUM_COLORS[2]),
+    ] {
+        let Some(ranked) = podium.iter().find(|ranked| ranked.rank == rank) else {
+            continue;
+        };
+        let left = step_width.mul_add(column as f32, rect.min.x);
+        let step = Rect::from_min_max(
+            Pos2::new(left + 4.0, rect.height().mul_add(-height, rect.max.y)),
+            Pos2::new(left + step_width - 4.0, rect.max.y),
+        );
+        painter.rect_filled(step, CornerRadius::ZERO, color);
+        painter.text(
+            step.center_top() - Vec2::new(0.0, 4.0),
+            Align2::CENTER_BOTTOM,
+            &ranked.name,
+            FontId::proportional(24.0),
+            ui.style().visuals.text_color(),
+        );
+        painter.text(
+            step.center_top() + Vec2::new(0.0, 8.0),
+            Align2::CENTER_TOP,
+            format!("#{rank}"),
+            FontId::proportional(32.0),
+            Color32::BLACK,
+        );
+        painter.text(
+            step.center_bottom() - Vec2::new(0.0, 8.0),
+            Align2::CENTER_BOTTOM,
+            format!("{}", ranked.score),
+            FontId::proportional(18.0),
+            Color32::BLACK,
+        );
+    }
+}
+
+/// The value of a query parameter in the page URL, empty if it has none
+fn query_param(key: &str) -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    search.trim_start_matches('?').split('&').find_map(|pair| {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (name == key).then(|| value.to_owned())
+    })
+}
+
+/// The room to join, taken from the `?room=` query parameter
+fn room_from_location() -> Option<String> {
+    query_param("room").filter(|room| !room.is_empty())
+}
+
+/// Where the resume token of the player called `label` in this room is kept.
+/// Session storage belongs to the tab, so a reload resumes the same player
+/// while another tab joins as someone else.
+fn resume_token_key(label: &str) -> String {
+    format!(
+        "resume-token:{}:{}",
+        room_from_location().unwrap_or_default(),
+        label
+    )
+}
+
+fn session_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.session_storage().ok()?
+}
+
+fn load_resume_token(label: &str) -> Option<String> {
+    session_storage()?.get_item(&resume_token_key(label)).ok()?
+}
+
+fn store_resume_token(label: &str, resume_token: Option<&str>) {
+    let Some(storage) = session_storage() else {
+        return;
+    };
+    let key = resume_token_key(label);
+    // Storage can be full or turned off, then only dropped connections resume
+    let _ = match resume_token {
+        Some(resume_token) => storage.set_item(&key, resume_token),
+        None => storage.remove_item(&key),
+    };
+}
+
+/// Copies the roster with the players that left removed and the ones
+/// that joined inserted in name order, so the other cells stay in place
+fn apply_roster_changes(
+    people: &IndexMap<u32, Person>,
+    joined: &[PlayerJoined<'_>],
+    left: &[PlayerLeft],
+) -> IndexMap<u32, Person> {
+    let mut map: IndexMap<u32, Person> = people
+        .iter()
+        .filter(|(id, _)| !left.iter().any(|left| left.id == Some(**id)))
+        .map(|(id, person)| {
+            (
+                *id,
+                Person {
+                    name: person.name.clone(),
+                    score: person.score.load(Ordering::Relaxed).into(),
+                },
+            )
+        })
+        .collect();
+    for player in joined {
+        let (Some(id), Some(name)) = (player.id, player.name) else {
+            continue;
+        };
+        let person = Person {
+            name: name.to_owned(),
+            score: player.value.unwrap_or_default().into(),
+        };
+        if let Some(existing) = map.get_mut(&id) {
+            *existing = person;
+            continue;
+        }
+        let index = map
+            .binary_search_by(|_, other| other.name.as_str().cmp(name))
+            .unwrap_or_else(|index| index);
+        map.shift_insert(index, id, person);
+    }
+    map
+}
+
+/// Sends the player back to the join window, showing them why
+fn turn_away(shared: &Shared, reason: String) -> Ended {
+    shared.error.store(Arc::new(reason));
+    shared.joined.store(false, Ordering::Relaxed);
+    shared.events.send(ServerEvent::Rejected).unwrap();
+    Ended::Finished
+}
+
+/// Decides what to do about the error the server ended the connection with
+fn handle_error(shared: &Shared, error: &ErrorMessage<'_>) -> Ended {
+    let message = match error.code {
+        // The id lets whoever runs the server find out what went wrong
+        Some(ErrorCode::Internal) | None => format!(
+            "Something went wrong on the server, try again in a bit (error id {})",
+            error.id.unwrap_or("unknown")
+        ),
+        _ => error.message.unwrap_or_default().to_owned(),
+    };
+    if error.retry == Some(true) {
+        shared.error.store(Arc::new(message));
+        Ended::Dropped { welcomed: false }
+    } else {
+        turn_away(shared, message)
+    }
+}
+
+/// The server only sends binary frames, text means it's a different version
+fn unexpected_text(shared: &Shared) -> Ended {
+    turn_away(
+        shared,
+        "The server sent something this page doesn't understand, reload it".to_owned(),
+    )
+}
+
+/// What the app shares with the task talking to the server
+struct Shared {
     joined: Arc<AtomicBool>,
     error: Arc<ArcSwap<String>>,
-    people: Arc<ArcSwap<IndexMap<String, AtomicI64>>>,
-    label: String,
-    rx: flume::Receiver<Vec<(String, i64)>>,
-) {
+    people: Arc<ArcSwap<IndexMap<u32, Person>>>,
+    ranking: Arc<ArcSwap<Vec<Ranked>>>,
+    round: Arc<ArcSwapOption<Round>>,
+    events: flume::Sender<ServerEvent>,
+}
+
+/// How a connection to the server came to an end
+enum Ended {
+    /// The server turned us away or the app stopped listening, trying again won't help
+    Finished,
+    /// The connection dropped, reconnecting can pick the game back up
+    Dropped { welcomed: bool },
+}
+
+/// Talks to the server for as long as the page is open, reconnecting with
+/// backoff and resuming the same player whenever the connection drops
+async fn websocket(shared: Shared, label: String, rx: flume::Receiver<Outgoing>) {
+    // Set if the page was reloaded while playing under the same name
+    let mut resume_token = load_resume_token(&label);
+    let mut backoff = RECONNECT_MIN_BACKOFF;
+    loop {
+        match connect(&shared, &label, &rx, &mut resume_token).await {
+            Ended::Finished => return,
+            // Never got in, the join window shows what went wrong
+            Ended::Dropped { .. } if !shared.joined.load(Ordering::Relaxed) => return,
+            Ended::Dropped { welcomed } => {
+                if welcomed {
+                    backoff = RECONNECT_MIN_BACKOFF;
+                }
+            }
+        }
+        shared.events.send(ServerEvent::Disconnected).unwrap();
+        sleep(backoff).await;
+        backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+        // Clicks made while disconnected are stale by now
+        rx.drain();
+    }
+}
+
+/// Resolves after `duration` using the browser's timer
+async fn sleep(duration: Duration) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        web_sys::window()
+            .expect("No window")
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                duration.as_millis() as i32,
+            )
+            .expect("Failed to set timeout");
+    });
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .expect("Timers don't fail");
+}
+
+/// Connects to the server, resuming the game if there's a resume token,
+/// and relays messages until the connection ends
+async fn connect(
+    shared: &Shared,
+    label: &str,
+    rx: &flume::Receiver<Outgoing>,
+    resume_token: &mut Option<String>,
+) -> Ended {
+    let room = room_from_location()
+        .map(|room| format!("/{room}"))
+        .unwrap_or_default();
     let (_connection_meta, connection) = match WsMeta::connect(
         format!(
-            "wss://{}/redis-game/ws",
+            "wss://{}/redis-game/ws{}",
             web_sys::window()
                 .unwrap()
                 .window()
                 .location()
                 .host()
-                .unwrap()
+                .unwrap(),
+            room
         ),
         None,
     )
@@ -435,76 +1022,183 @@ async fn websocket(
     {
         Ok(conn) => conn,
         Err(e) => {
-            error.store(Arc::new(format!("{}", e)));
-            return;
+            shared.error.store(Arc::new(format!("{}", e)));
+            return Ended::Dropped { welcomed: false };
         }
     };
     let mut connection = connection.fuse();
-    joined.store(true, Ordering::Relaxed);
-    connection.send(WsMessage::Text(label)).await.unwrap();
+    // `?host=token` joins as a host, the token can be left out for host names
+    let host = query_param("host");
+    let mut hello = Vec::new();
+    Hello {
+        protocol_version: Some(PROTOCOL_VERSION),
+        client_name: Some(label),
+        capabilities: Some(CAPABILITIES.to_vec()),
+        role: Some(if host.is_some() {
+            Role::Host
+        } else {
+            Role::Player
+        }),
+        host_token: host.as_deref().filter(|token| !token.is_empty()),
+        resume_token: resume_token.as_deref(),
+    }
+    .serialize(&mut hello)
+    .unwrap();
+    if connection.send(WsMessage::Binary(hello)).await.is_err() {
+        return Ended::Dropped { welcomed: false };
+    }
+    match connection.next().await {
+        Some(WsMessage::Binary(welcome)) => match Welcome::deserialize(&welcome) {
+            Ok(welcome) => {
+                if let Some(error) = welcome.error {
+                    return handle_error(shared, &error);
+                }
+                if let Some(name_error) = welcome.name_error {
+                    // Back to the join window to pick another name
+                    return turn_away(shared, name_error.to_owned());
+                }
+                *resume_token = welcome.resume_token.map(str::to_owned);
+                store_resume_token(label, resume_token.as_deref());
+                shared
+                    .events
+                    .send(ServerEvent::Welcome {
+                        host: welcome.role == Some(Role::Host),
+                        player_id: welcome.player_id,
+                        rules: welcome.rules.map(GameRules::from).unwrap_or_default(),
+                        powerups: welcome
+                            .capabilities
+                            .is_some_and(|agreed| agreed.contains(&"powerups")),
+                    })
+                    .unwrap();
+            }
+            Err(e) => {
+                shared
+                    .error
+                    .store(Arc::new(format!("Failed to parse welcome message: {e}")));
+                return Ended::Finished;
+            }
+        },
+        Some(WsMessage::Text(_)) => return unexpected_text(shared),
+        None => {
+            shared.error.store(Arc::new(
+                "Connection closed before the server said welcome".to_owned(),
+            ));
+            return Ended::Dropped { welcomed: false };
+        }
+    }
+    shared.joined.store(true, Ordering::Relaxed);
     loop {
         futures_util::select_biased! {
-            click = rx.recv_async() => if let Ok(clicks) = click {
-                let mut clicks_buf = Vec::new();
-                for (name, amount) in &clicks {
-                    clicks_buf.push(KeyValue {
-                        key: name,
-                        value: *amount
-                    });
-                }
+            outgoing = rx.recv_async() => {
+                let Ok(outgoing) = outgoing else {
+                    // The app went away
+                    return Ended::Finished;
+                };
+                let message = match outgoing {
+                    Outgoing::Clicks(clicks) => GameMessage {
+                        clicks: Some(
+                            clicks
+                                .into_iter()
+                                .map(|(id, assist)| Click { id: Some(id), assist: Some(assist) })
+                                .collect(),
+                        ),
+                        ..Default::default()
+                    },
+                    Outgoing::ActivatePowerup => GameMessage {
+                        activate_powerup: Some(true),
+                        ..Default::default()
+                    },
+                };
                 let mut buf = Vec::new();
-                GameMessage {
-                    updates: None,
-                    clicks: Some(clicks_buf),
-                    clear: None
+                message.serialize(&mut buf).unwrap();
+                if connection.send(WsMessage::Binary(buf)).await.is_err() {
+                    return Ended::Dropped { welcomed: true };
                 }
-                .serialize(&mut buf)
-                .unwrap();
-                connection.send(WsMessage::Binary(buf)).await.unwrap();
             },
-            message = connection.next() => if let Some(message) = message {
-                match message {
-                    WsMessage::Binary(message) => {
-                        if let Ok(message) = GameMessage::deserialize(&message) {
-                            if message.clear == Some(true) {
-                                let mut map = IndexMap::default();
-                                if let Some(mut updates) = message.updates {
-                                    updates.sort_by_key(|kv| kv.key);
-                                    for key_value in updates {
-                                        map.insert(key_value.key.to_owned(), key_value.value.into());
-                                    }
-                                }
-                                web_sys::console::log_1(&format!("{:?}", map).into());
-                                people.store(Arc::new(map));
-                            } else {
-                                let map = people.load();
-                                if let Some(updates) = message.updates {
-                                    for key_value in updates {
-                                        if let Some(person) = map.get(key_value.key) {
-                                            person.store(key_value.value, Ordering::Relaxed);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    WsMessage::Text(error) => {
-                        let document = web_sys::window()
-                            .expect("No window")
-                            .document()
-                            .expect("No document");
-
-                        let div = document
-                            .get_element_by_id("page")
-                            .expect("Failed to find page div")
-                            .dyn_into::<web_sys::HtmlDivElement>()
-                            .expect("page was not a HtmlDivElement");
-
-                        div.set_inner_html(&error);
-                        return;
+            message = connection.next() => match message {
+                Some(WsMessage::Binary(message)) => {
+                    if let Some(ended) = handle_game_message(shared, &message) {
+                        return ended;
                     }
                 }
+                Some(WsMessage::Text(_)) => return unexpected_text(shared),
+                None => return Ended::Dropped { welcomed: true },
+            }
+        }
+    }
+}
+
+/// Applies a game message from the server to the shared state,
+/// returning how the connection ended if the server ended it
+fn handle_game_message(shared: &Shared, message: &[u8]) -> Option<Ended> {
+    let Ok(message) = GameMessage::deserialize(message) else {
+        return None;
+    };
+    if let Some(error) = message.error {
+        return Some(handle_error(shared, &error));
+    }
+    if let Some(round) = &message.round {
+        shared.round.store(Some(Arc::new(Round::from(round))));
+    }
+    if let Some(leaderboard) = message.leaderboard {
+        shared.ranking.store(Arc::new(
+            leaderboard
+                .into_iter()
+                .map(|ranked| Ranked {
+                    rank: ranked.rank,
+                    name: ranked.key.to_owned(),
+                    score: ranked.value,
+                })
+                .collect(),
+        ));
+    }
+    if message.clear == Some(true) {
+        let mut map = IndexMap::default();
+        if let Some(mut players) = message.players {
+            players.sort_by_key(|player| player.name);
+            for player in players {
+                map.insert(
+                    player.id,
+                    Person {
+                        name: player.name.to_owned(),
+                        score: player.value.into(),
+                    },
+                );
+            }
+        }
+        web_sys::console::log_1(&format!("{:?}", map).into());
+        shared.people.store(Arc::new(map));
+        return None;
+    }
+    if message.throttled == Some(true) {
+        shared.events.send(ServerEvent::Throttled).unwrap();
+    }
+    if let Some(powerup) = message.powerup {
+        shared
+            .events
+            .send(ServerEvent::Powerup {
+                kind: powerup.kind.map(Powerup::from),
+                active_for: Duration::from_millis(powerup.active_ms.unwrap_or_default().into()),
+                ready_in: Duration::from_millis(powerup.ready_in_ms.unwrap_or_default().into()),
+            })
+            .unwrap();
+    }
+    let joined = message.joined.unwrap_or_default();
+    let left = message.left.unwrap_or_default();
+    if !joined.is_empty() || !left.is_empty() {
+        shared.people.store(Arc::new(apply_roster_changes(
+            &shared.people.load(),
+            &joined,
+            &left,
+        )));
+    }
+    if let (Some(ids), Some(values)) = (message.delta_ids, message.delta_values) {
+        let map = shared.people.load();
+        for (id, value) in ids.iter().zip(values.iter()) {
+            if let Some(person) = map.get(&id) {
+                person.score.store(value, Ordering::Relaxed);
             }
         }
     }
+    None
 }
@@ -0,0 +1,70 @@
+//! Optional features a client and the server agree on in the handshake.
+//!
+//! A client lists what it can handle in its `Hello` and the server answers
+//! with the ones it supports in the `Welcome`. Everything else is left out
+//! of the game, so a lighter client can still play without them.
+
+/// Picking the game back up after a dropped connection with a resume token
+pub const RESUME: &str = "resume";
+/// Activating powerups and clicking several cells with them
+pub const POWERUPS: &str = "powerups";
+
+/// What the server and a client agreed to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    pub resume: bool,
+    pub powerups: bool,
+}
+
+impl Capabilities {
+    /// Keeps the capabilities the server supports, unknown ones are ignored
+    pub fn negotiate<'a>(requested: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut agreed = Self::default();
+        for capability in requested {
+            match capability {
+                RESUME => agreed.resume = true,
+                POWERUPS => agreed.powerups = true,
+                _ => {}
+            }
+        }
+        agreed
+    }
+
+    /// Names of the agreed capabilities for the `Welcome`
+    pub fn names(self) -> Vec<&'static str> {
+        [(RESUME, self.resume), (POWERUPS, self.powerups)]
+            .into_iter()
+            .filter_map(|(name, agreed)| agreed.then_some(name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agrees_to_supported_capabilities() {
+        let agreed = Capabilities::negotiate(["powerups", "teleport", "resume"]);
+        assert_eq!(
+            agreed,
+            Capabilities {
+                resume: true,
+                powerups: true
+            }
+        );
+        assert_eq!(agreed.names(), ["resume", "powerups"]);
+    }
+
+    #[test]
+    fn leaves_out_what_was_not_asked_for() {
+        let agreed = Capabilities::negotiate(["resume", "resume"]);
+        assert!(agreed.resume);
+        assert!(!agreed.powerups);
+        assert_eq!(agreed.names(), ["resume"]);
+
+        let agreed = Capabilities::negotiate([]);
+        assert_eq!(agreed, Capabilities::default());
+        assert!(agreed.names().is_empty());
+    }
+}
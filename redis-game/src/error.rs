@@ -4,21 +4,56 @@
 //! This crate uses `eyre` to capture the error,
 //! the error is then returned to the browser or
 //! whatever it is, it's then nicely formatted to
-//! a webpage using `ansi_to_html`
-use std::fmt::{Debug, Display};
+//! a webpage using `ansi_to_html`, or to JSON for
+//! clients that `Accept: application/json`.
+//!
+//! Unless errors are exposed, clients only get a short
+//! id for server errors, the full report is logged under it.
+use std::{
+    fmt::{Debug, Display},
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use axum::{
+    Json,
     body::Body,
-    http::StatusCode,
-    response::{Html, IntoResponse},
+    extract::Request,
+    http::{StatusCode, header::ACCEPT},
+    middleware::Next,
+    response::{Html, IntoResponse, Response},
 };
 use color_eyre::eyre::eyre;
+use serde::Serialize;
 use tower_http::catch_panic::ResponseForPanic;
 
+use crate::messages::redis_game::{ErrorCode, ErrorMessage};
+
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Told to clients instead of what actually went wrong on the server
+const GENERIC_MESSAGE: &str = "Something went wrong on the server";
+const ERROR_ID_LEN: usize = 10;
+
+static EXPOSE_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Sends clients the full report of every error, meant for development
+pub fn expose_errors(expose: bool) {
+    EXPOSE_ERRORS.store(expose, Ordering::Relaxed);
+}
+
 pub struct Error(pub StatusCode, pub color_eyre::eyre::Report);
 
+/// What a client gets to see of an error
+pub struct Public {
+    /// Finds the full report in the logs
+    pub id: String,
+    pub code: ErrorCode,
+    pub message: String,
+    pub retry: bool,
+    /// Error page for browsers
+    pub page: String,
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let ansi_string = format!("{:?}", self);
@@ -31,6 +66,96 @@ impl Display for Error {
     }
 }
 
+impl Error {
+    /// Machine readable kind of the error, picked from its status code
+    pub fn code(&self) -> ErrorCode {
+        match self.0 {
+            StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED => ErrorCode::Forbidden,
+            StatusCode::NOT_FOUND => ErrorCode::NotFound,
+            StatusCode::CONFLICT => ErrorCode::Conflict,
+            StatusCode::UPGRADE_REQUIRED => ErrorCode::OutOfDate,
+            code if code.is_client_error() => ErrorCode::BadRequest,
+            _ => ErrorCode::Internal,
+        }
+    }
+
+    /// Whether trying again can work, only the server's own failures might go away
+    pub fn retry(&self) -> bool {
+        self.0.is_server_error()
+    }
+
+    /// Logs the full report under a new id and returns what the client gets to see of it,
+    /// client errors say what was wrong with the request, server errors only give the id
+    pub fn publish(&self) -> Public {
+        let id = nanoid::nanoid!(ERROR_ID_LEN);
+        // A misbehaving client shouldn't be able to flood the error logs
+        if self.0.is_server_error() {
+            tracing::error!(error_id = id, error = ?self);
+        } else {
+            tracing::info!(error_id = id, error = ?self);
+        }
+
+        let exposed = EXPOSE_ERRORS.load(Ordering::Relaxed);
+        let message = if exposed || !self.0.is_server_error() {
+            // Only the outermost message, without its causes or spans
+            self.1.to_string()
+        } else {
+            GENERIC_MESSAGE.to_owned()
+        };
+        let page = if exposed {
+            format!("{}", self)
+        } else {
+            format!(
+                "<!DOCTYPE html><html><head><meta charset=\"utf8\"></head><body><p>{}</p><p>Error id: <code>{}</code></p></body></html>",
+                ansi_to_html::Esc(&message),
+                id
+            )
+        };
+
+        Public {
+            id,
+            code: self.code(),
+            message,
+            retry: self.retry(),
+            page,
+        }
+    }
+}
+
+impl Public {
+    /// Serializes the error for WebSocket clients
+    pub fn to_message(&self) -> ErrorMessage<'_> {
+        ErrorMessage {
+            code: Some(self.code),
+            message: Some(&self.message),
+            retry: Some(self.retry),
+            id: Some(&self.id),
+        }
+    }
+}
+
+/// Body of the JSON error responses
+#[derive(Clone, Serialize)]
+struct ErrorBody {
+    id: String,
+    code: &'static str,
+    message: String,
+    retry: bool,
+}
+
+impl ErrorCode {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Internal => "internal",
+            Self::BadRequest => "bad_request",
+            Self::OutOfDate => "out_of_date",
+            Self::Forbidden => "forbidden",
+            Self::NotFound => "not_found",
+            Self::Conflict => "conflict",
+        }
+    }
+}
+
 impl Debug for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.1.handler().debug(self.1.as_ref(), f)
@@ -39,8 +164,34 @@ impl Debug for Error {
 
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
-        (self.0, Html(format!("{}", self))).into_response()
+        let public = self.publish();
+        let body = ErrorBody {
+            id: public.id,
+            code: public.code.as_str(),
+            message: public.message,
+            retry: public.retry,
+        };
+        let mut response = (self.0, Html(public.page)).into_response();
+        // Picked up by `negotiate` if the client wants JSON instead
+        response.extensions_mut().insert(body);
+        response
+    }
+}
+
+/// Middleware swapping the HTML error page for JSON
+/// when the client `Accept`s `application/json`
+pub async fn negotiate(request: Request, next: Next) -> Response {
+    let json = request
+        .headers()
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|accept| accept.to_str().ok())
+        .any(|accept| accept.contains("application/json"));
+    let mut response = next.run(request).await;
+    if json && let Some(body) = response.extensions_mut().remove::<ErrorBody>() {
+        return (response.status(), Json(body)).into_response();
     }
+    response
 }
 
 impl From<Error> for Box<dyn std::error::Error + Sync + Send> {
@@ -0,0 +1,55 @@
+//! Endpoints telling Kubernetes whether a replica can take players
+use std::time::Duration;
+
+use axum::{Json, extract::State, http::StatusCode};
+use serde::Serialize;
+
+use crate::AppState;
+
+/// How long Redis gets to answer the readiness check's `PING`
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Body of the health responses
+#[derive(Serialize)]
+pub struct Health {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redis: Option<&'static str>,
+}
+
+/// The process is up and serving requests
+pub async fn healthz() -> Json<Health> {
+    Json(Health {
+        status: "ok",
+        redis: None,
+    })
+}
+
+/// Players can be sent here, Redis answers on the shared connection.
+/// Getting here at all means the listener is accepting connections.
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<Health>) {
+    let mut connection = state.hub.connection();
+    let ping = redis::cmd("PING");
+    match tokio::time::timeout(PING_TIMEOUT, ping.query_async::<String>(&mut connection)).await {
+        Ok(Ok(_)) => (
+            StatusCode::OK,
+            Json(Health {
+                status: "ok",
+                redis: Some("ok"),
+            }),
+        ),
+        result => {
+            match result {
+                Ok(Err(e)) => tracing::warn!(?e, "Redis failed the readiness check"),
+                _ => tracing::warn!("Redis timed out during the readiness check"),
+            }
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(Health {
+                    status: "unavailable",
+                    redis: Some("unreachable"),
+                }),
+            )
+        }
+    }
+}
@@ -0,0 +1,795 @@
+//! Process-wide fan-out of room updates to local sockets
+//!
+//! A single task listens to the channels of every room and keeps the
+//! scores of the rooms that have sockets on this replica in memory.
+//! Every tick it serializes what changed once and broadcasts the frame
+//! to all of the room's sockets, so Redis is read once per room instead
+//! of once per socket.
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{body::Bytes, http::StatusCode};
+use bebop::{Record, SliceWrapper};
+use color_eyre::eyre::{self, Context, OptionExt};
+use redis::{
+    AsyncTypedCommands, Client, PushInfo, PushKind, Value,
+    aio::{ConnectionManager, ConnectionManagerConfig},
+};
+use tokio::{
+    sync::{broadcast, mpsc, watch},
+    time,
+};
+
+use crate::{
+    error::{self, WithStatusCode},
+    messages::redis_game::{GameMessage, Player, PlayerJoined, PlayerLeft, RankedKeyValue},
+    metrics::{self, METRICS},
+    room::{self, Channel, Room},
+    round::{self, Round},
+    rules::GameRules,
+};
+
+/// How often the players whose grace period ran out are removed
+const REAP_INTERVAL: Duration = Duration::from_secs(1);
+/// How often the rounds of the rooms on this replica are checked on,
+/// so they move on this long after their phase ends at the latest
+const ROUND_INTERVAL: Duration = Duration::from_millis(250);
+/// Frames a slow socket may fall behind before it's sent a fresh snapshot
+const GRID_BACKLOG: usize = 64;
+
+/// The scores of a room's players and their ids, as read from Redis
+type Roster = (Vec<(String, f64)>, HashMap<String, u32>);
+
+pub struct Hub {
+    redis: ConnectionManager,
+    leaderboard_size: u32,
+    rules: GameRules,
+    rooms: Mutex<HashMap<Room, Arc<Mutex<RoomState>>>>,
+}
+
+struct PlayerState {
+    id: u32,
+    score: i64,
+}
+
+struct RoomState {
+    players: HashMap<String, PlayerState>,
+    /// Players whose score changed since the last tick
+    changed: HashSet<String>,
+    /// Players that joined since the last tick
+    joined: Vec<String>,
+    /// Ids of the players that left since the last tick
+    left: Vec<u32>,
+    /// The room was just opened or missed updates, its players are read from Redis
+    reload: bool,
+    /// The top of the room may have changed since the leaderboard was sent
+    leaderboard_dirty: bool,
+    /// Not known until the room's round is first checked on
+    round: Option<Round>,
+    grid: broadcast::Sender<Bytes>,
+    leaderboard: watch::Sender<Bytes>,
+}
+
+impl RoomState {
+    fn new() -> Self {
+        Self {
+            players: HashMap::new(),
+            changed: HashSet::new(),
+            joined: Vec::new(),
+            left: Vec::new(),
+            reload: true,
+            leaderboard_dirty: true,
+            round: None,
+            grid: broadcast::channel(GRID_BACKLOG).0,
+            leaderboard: watch::channel(Bytes::new()).0,
+        }
+    }
+
+    fn is_watched(&self) -> bool {
+        self.grid.receiver_count() > 0 || self.leaderboard.receiver_count() > 0
+    }
+
+    /// Every player in the room, telling the client to rebuild its grid
+    fn snapshot(&self) -> eyre::Result<Bytes> {
+        serialize(GameMessage {
+            players: Some(
+                self.players
+                    .iter()
+                    .map(|(name, player)| Player {
+                        id: player.id,
+                        name,
+                        value: player.score,
+                    })
+                    .collect(),
+            ),
+            clear: Some(true),
+            round: self.round.as_ref().map(Round::to_message),
+            ..Default::default()
+        })
+    }
+
+    /// The snapshot for a socket that just subscribed or fell behind, unless
+    /// the players are about to be read from Redis and the next tick sends one
+    fn current_snapshot(&self) -> eyre::Result<Option<Bytes>> {
+        if self.reload {
+            return Ok(None);
+        }
+        self.snapshot().map(Some)
+    }
+
+    /// Replaces the players with the ones read from Redis, the snapshot
+    /// sent next has everything that changed since the last tick
+    fn load(&mut self, (scores, mut ids): Roster) {
+        self.players = scores
+            .into_iter()
+            .filter_map(|(name, score)| {
+                let id = ids.remove(&name)?;
+                Some((
+                    name,
+                    PlayerState {
+                        id,
+                        score: score as i64,
+                    },
+                ))
+            })
+            .collect();
+        self.changed.clear();
+        self.joined.clear();
+        self.left.clear();
+    }
+
+    /// Applies what was published on one of the room's channels,
+    /// returning whether the payload could be read
+    fn apply(&mut self, channel: Channel, payload: &str) -> bool {
+        match channel {
+            Channel::Joins => {
+                let Some((id, score, name)) = payload.split_once(' ').and_then(|(id, rest)| {
+                    let (score, name) = rest.split_once(' ')?;
+                    Some((id.parse().ok()?, score.parse::<i64>().ok()?, name))
+                }) else {
+                    return false;
+                };
+                // Whoever had the name before is gone if the id changed
+                if let Some(previous) = self
+                    .players
+                    .insert(name.to_owned(), PlayerState { id, score })
+                    && previous.id != id
+                {
+                    self.left.push(previous.id);
+                }
+                self.joined.push(name.to_owned());
+                self.leaderboard_dirty = true;
+            }
+            Channel::Leaves => {
+                let Some((id, name)) = payload
+                    .split_once(' ')
+                    .and_then(|(id, name)| Some((id.parse::<u32>().ok()?, name)))
+                else {
+                    return false;
+                };
+                if self.players.get(name).is_some_and(|player| player.id == id) {
+                    self.players.remove(name);
+                    self.changed.remove(name);
+                    self.joined.retain(|joined| joined != name);
+                    self.left.push(id);
+                    self.leaderboard_dirty = true;
+                }
+            }
+            Channel::ScoreUpdates => {
+                let Some((score, name)) = payload.split_once(' ') else {
+                    return false;
+                };
+                let Ok(score) = score.parse::<f64>() else {
+                    return false;
+                };
+                if let Some(player) = self.players.get_mut(name) {
+                    player.score = score as i64;
+                    self.changed.insert(name.to_owned());
+                    self.leaderboard_dirty = true;
+                }
+            }
+        }
+        true
+    }
+
+    /// Whether to send the leaderboard this tick, it's only kept up while someone watches it
+    fn take_leaderboard_dirty(&mut self) -> bool {
+        self.leaderboard.receiver_count() > 0 && std::mem::take(&mut self.leaderboard_dirty)
+    }
+
+    /// What the grid gets this tick: a snapshot if the players were just
+    /// read from Redis, otherwise what changed if anything did
+    fn grid_frame(&mut self, roster: Option<Roster>) -> eyre::Result<Option<Bytes>> {
+        if let Some(roster) = roster {
+            self.load(roster);
+            return self.snapshot().map(Some);
+        }
+        if self.has_delta() {
+            return self.delta().map(Some);
+        }
+        Ok(None)
+    }
+
+    fn has_delta(&self) -> bool {
+        !self.changed.is_empty() || !self.joined.is_empty() || !self.left.is_empty()
+    }
+
+    /// The players that joined or left and the scores that changed since the last tick
+    fn delta(&mut self) -> eyre::Result<Bytes> {
+        let (ids, values): (Vec<_>, Vec<_>) = self
+            .changed
+            .drain()
+            .filter_map(|name| {
+                let player = self.players.get(&name)?;
+                Some((player.id, player.score))
+            })
+            .unzip();
+        let joined = std::mem::take(&mut self.joined);
+        let left = std::mem::take(&mut self.left);
+        serialize(GameMessage {
+            delta_ids: Some(SliceWrapper::Cooked(&ids)),
+            delta_values: Some(SliceWrapper::Cooked(&values)),
+            joined: Some(
+                joined
+                    .iter()
+                    .filter_map(|name| {
+                        let player = self.players.get(name)?;
+                        Some(PlayerJoined {
+                            id: Some(player.id),
+                            name: Some(name),
+                            value: Some(player.score),
+                        })
+                    })
+                    .collect(),
+            ),
+            left: Some(
+                left.into_iter()
+                    .map(|id| PlayerLeft { id: Some(id) })
+                    .collect(),
+            ),
+            ..Default::default()
+        })
+    }
+}
+
+/// What a socket receives from the hub
+pub enum Subscription {
+    Grid(broadcast::Receiver<Bytes>),
+    Leaderboard(watch::Receiver<Bytes>),
+}
+
+impl Hub {
+    /// Opens the shared Redis connections and subscribes to every room's
+    /// channels, the returned future has to be polled for the hub to run.
+    pub async fn new(
+        client: &Client,
+        leaderboard_size: u32,
+        rules: GameRules,
+    ) -> eyre::Result<(Arc<Self>, impl Future<Output = eyre::Result<()>>)> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        // The manager reconnects after Redis drops and subscribes again
+        let config = ConnectionManagerConfig::new()
+            .set_push_sender(tx)
+            .set_automatic_resubscription();
+        let mut pubsub = client
+            .get_connection_manager_with_config(config)
+            .await
+            .wrap_err("Failed to open the hub's pub/sub connection")?;
+        pubsub
+            .psubscribe(&room::ALL_ROOM_CHANNELS)
+            .await
+            .wrap_err("Failed to subscribe to the room channels")?;
+
+        let hub = Arc::new(Self {
+            redis: client
+                .get_connection_manager()
+                .await
+                .wrap_err("Failed to open the hub's Redis connection")?,
+            leaderboard_size,
+            rules,
+            rooms: Mutex::new(HashMap::new()),
+        });
+
+        let run = Arc::clone(&hub).run(pubsub, rx);
+        Ok((hub, run))
+    }
+
+    /// A handle to the shared connection for commands that don't need
+    /// their own connection
+    pub fn connection(&self) -> ConnectionManager {
+        self.redis.clone()
+    }
+
+    pub fn subscribe(&self, room: &Room, leaderboard: bool) -> Subscription {
+        let mut rooms = self.rooms.lock().unwrap();
+        let state = rooms
+            .entry(room.clone())
+            .or_insert_with(|| Arc::new(Mutex::new(RoomState::new())));
+        let mut state = state.lock().unwrap();
+        if leaderboard {
+            state.leaderboard_dirty = true;
+            Subscription::Leaderboard(state.leaderboard.subscribe())
+        } else {
+            Subscription::Grid(state.grid.subscribe())
+        }
+    }
+
+    /// The room's current scores, for sockets that just subscribed or fell behind.
+    /// `None` while the room is still being read, its first tick sends them.
+    pub fn snapshot(&self, room: &Room) -> error::Result<Option<Bytes>> {
+        let state = self
+            .rooms
+            .lock()
+            .unwrap()
+            .get(room)
+            .cloned()
+            .ok_or_eyre("Room has no subscribers")
+            .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+        let state = state.lock().unwrap();
+        state
+            .current_snapshot()
+            .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    /// Where each player's cell is in the room's grid, laid out the same way
+    /// the frontend draws it: sorted by name and filled row by row
+    pub fn grid_cells(&self, room: &Room) -> HashMap<u32, (usize, usize)> {
+        let Some(state) = self.rooms.lock().unwrap().get(room).cloned() else {
+            return HashMap::new();
+        };
+        let state = state.lock().unwrap();
+        let mut players: Vec<_> = state.players.iter().collect();
+        players.sort_unstable_by_key(|(name, _)| *name);
+
+        let len = players.len();
+        let stride = if len <= 2 {
+            len
+        } else {
+            (len as f64 + (len % 2) as f64).sqrt().ceil() as usize
+        };
+        players
+            .into_iter()
+            .enumerate()
+            .map(|(i, (_, player))| (player.id, (i % stride, i / stride)))
+            .collect()
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        // Kept alive for as long as the hub runs, dropping it ends the subscriptions
+        mut pubsub: ConnectionManager,
+        mut rx: mpsc::UnboundedReceiver<PushInfo>,
+    ) -> eyre::Result<()> {
+        let mut interval = time::interval(self.rules.tick);
+        interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+        let mut reap_interval = time::interval(REAP_INTERVAL);
+        reap_interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+        let mut round_interval = time::interval(ROUND_INTERVAL);
+        round_interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                msg = rx.recv() => {
+                    let msg = msg.ok_or_eyre("The hub's pub/sub connection was closed")?;
+                    self.handle_push(msg);
+                }
+                _ = interval.tick() => {
+                    self.tick().await;
+                }
+                _ = reap_interval.tick() => {
+                    self.reap().await;
+                    // The manager only retries a few times after a drop,
+                    // a command makes it try again while Redis is down
+                    if let Err(e) = pubsub.ping().await {
+                        tracing::warn!(?e, "Redis pub/sub connection is down");
+                    }
+                }
+                // Endless games never move on
+                _ = round_interval.tick(), if self.rules.rounds() => {
+                    self.advance_rounds().await;
+                }
+            }
+        }
+    }
+
+    /// Removes the disconnected players of the rooms on this replica once
+    /// their grace period runs out, their leaves come back over pub/sub
+    async fn reap(&self) {
+        let rooms = self
+            .rooms
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut redis = self.redis.clone();
+        for room in rooms {
+            match room::reap(&mut redis, &room).await {
+                Ok(0) => {}
+                Ok(reaped) => tracing::debug!(%room, reaped, "Reaped disconnected players"),
+                Err(e) => tracing::error!(%room, ?e, "Failed to reap disconnected players"),
+            }
+        }
+    }
+
+    /// Moves the rounds of the rooms on this replica on once their phase
+    /// ends, telling the rooms' sockets whenever one did
+    async fn advance_rounds(&self) {
+        let rooms = self
+            .rooms
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(room, state)| (room.clone(), Arc::clone(state)))
+            .collect::<Vec<_>>();
+        let mut redis = self.redis.clone();
+        for (room, state) in rooms {
+            let round = match round::advance(&mut redis, &room, &self.rules).await {
+                Ok(round) => round,
+                Err(e) => {
+                    tracing::error!(%room, ?e, "Failed to advance the round");
+                    continue;
+                }
+            };
+            let mut state = state.lock().unwrap();
+            if state
+                .round
+                .as_ref()
+                .is_some_and(|known| !round.moved_on_from(known))
+            {
+                continue;
+            }
+            tracing::debug!(%room, number = round.number, phase = ?round.phase, "Round moved on");
+            // Starting a round reset every score in Redis
+            if state
+                .round
+                .as_ref()
+                .is_some_and(|known| known.number != round.number)
+            {
+                state.reload = true;
+            }
+            let frame = serialize(GameMessage {
+                round: Some(round.to_message()),
+                ..Default::default()
+            });
+            match frame {
+                Ok(frame) => {
+                    let _ = state.grid.send(frame);
+                }
+                Err(e) => tracing::error!(%room, ?e),
+            }
+            state.round = Some(round);
+            state.leaderboard_dirty = true;
+        }
+    }
+
+    /// Everything published while the connection was down is lost, so
+    /// every room is read from Redis again
+    fn reload_rooms(&self) {
+        for state in self.rooms.lock().unwrap().values() {
+            let mut state = state.lock().unwrap();
+            state.reload = true;
+            state.leaderboard_dirty = true;
+        }
+    }
+
+    fn handle_push(&self, msg: PushInfo) {
+        if msg.kind == PushKind::Disconnection {
+            tracing::warn!("Lost the Redis pub/sub connection, reconnecting");
+            self.reload_rooms();
+            return;
+        }
+        let PushInfo {
+            kind: PushKind::PMessage,
+            mut data,
+        } = msg
+        else {
+            return;
+        };
+        let (Some(Value::BulkString(payload)), Some(Value::BulkString(channel))) =
+            (data.pop(), data.pop())
+        else {
+            return;
+        };
+        let Some((room, channel)) = Room::parse_channel(&String::from_utf8_lossy(&channel)) else {
+            return;
+        };
+        // Rooms without local sockets aren't kept in memory
+        let Some(state) = self.rooms.lock().unwrap().get(&room).cloned() else {
+            return;
+        };
+        let Ok(payload) = String::from_utf8(payload) else {
+            tracing::warn!(%room, ?channel, "Payload was not valid UTF-8");
+            return;
+        };
+        if !state.lock().unwrap().apply(channel, &payload) {
+            tracing::warn!(%room, ?channel, payload, "Malformed payload");
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn tick(&self) {
+        let start = Instant::now();
+        let rooms = self
+            .rooms
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(room, state)| (room.clone(), Arc::clone(state)))
+            .collect::<Vec<_>>();
+
+        for (room, state) in rooms {
+            if let Err(e) = self.tick_room(&room, &state).await {
+                tracing::error!(%room, ?e);
+            }
+        }
+
+        self.rooms
+            .lock()
+            .unwrap()
+            .retain(|_, state| state.lock().unwrap().is_watched());
+        METRICS.tick_duration.observe(start.elapsed().as_secs_f64());
+    }
+
+    #[tracing::instrument(skip(self, state), fields(%room))]
+    async fn tick_room(&self, room: &Room, state: &Mutex<RoomState>) -> eyre::Result<()> {
+        let (reload, leaderboard) = {
+            let mut state = state.lock().unwrap();
+            let leaderboard = state.take_leaderboard_dirty();
+            (std::mem::take(&mut state.reload), leaderboard)
+        };
+
+        let mut redis = self.redis.clone();
+        let players = if reload {
+            let players: redis::RedisResult<Roster> = metrics::timed(
+                "read_room",
+                redis::pipe()
+                    .zrange_withscores(room.scores_key(), 0, -1)
+                    .hgetall(room.player_ids_key())
+                    .query_async(&mut redis),
+            )
+            .await;
+            match players {
+                Ok(players) => Some(players),
+                Err(e) => {
+                    state.lock().unwrap().reload = true;
+                    return Err(e).wrap_err("Failed to read the room's scores");
+                }
+            }
+        } else {
+            None
+        };
+        let top = if leaderboard {
+            // Leaderboards only need the top of the room, already ranked by Redis
+            let top: redis::RedisResult<Vec<(String, f64)>> = metrics::timed(
+                "read_leaderboard",
+                redis::cmd("ZRANGE")
+                    .arg(room.scores_key())
+                    .arg(0)
+                    .arg(self.leaderboard_size.saturating_sub(1))
+                    .arg("REV")
+                    .arg("WITHSCORES")
+                    .query_async(&mut redis),
+            )
+            .await;
+            match top {
+                Ok(top) => Some(top),
+                Err(e) => {
+                    state.lock().unwrap().leaderboard_dirty = true;
+                    return Err(e).wrap_err("Failed to read the leaderboard");
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut state = state.lock().unwrap();
+        if let Some(frame) = state.grid_frame(players)? {
+            // Nobody is listening if this fails, which is fine
+            let _ = state.grid.send(frame);
+        }
+
+        if let Some(top) = top {
+            let frame = serialize(GameMessage {
+                leaderboard: Some(
+                    top.iter()
+                        .zip(1..)
+                        .map(|((key, value), rank)| RankedKeyValue {
+                            rank,
+                            key,
+                            value: *value as i64,
+                        })
+                        .collect(),
+                ),
+                round: state.round.as_ref().map(Round::to_message),
+                ..Default::default()
+            })?;
+            state.leaderboard.send_replace(frame);
+        }
+
+        Ok(())
+    }
+}
+
+impl Subscription {
+    /// Waits for the next frame to send to the socket, `None` once the hub is gone
+    pub async fn recv(&mut self, hub: &Hub, room: &Room) -> Option<error::Result<Bytes>> {
+        match self {
+            Self::Grid(rx) => loop {
+                match rx.recv().await {
+                    Ok(frame) => {
+                        METRICS.send_backlog.observe(rx.len() as f64);
+                        return Some(Ok(frame));
+                    }
+                    // Missed updates can't be replayed, start over from the current scores
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        METRICS.lagged.inc();
+                        match hub.snapshot(room) {
+                            Ok(Some(snapshot)) => return Some(Ok(snapshot)),
+                            // The room is being read again, its next frame is a snapshot
+                            Ok(None) => {}
+                            Err(e) => return Some(Err(e)),
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            },
+            Self::Leaderboard(rx) => {
+                rx.changed().await.ok()?;
+                Some(Ok(rx.borrow_and_update().clone()))
+            }
+        }
+    }
+}
+
+pub fn serialize(message: GameMessage<'_>) -> eyre::Result<Bytes> {
+    let mut buf = Vec::new();
+    message
+        .serialize(&mut buf)
+        .wrap_err("Failed to serialize game message")?;
+    Ok(buf.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A room that was read from Redis with alice and bob in it
+    fn loaded() -> RoomState {
+        let mut state = RoomState::new();
+        let roster = (
+            vec![("alice".to_owned(), 10.0), ("bob".to_owned(), 20.0)],
+            HashMap::from([("alice".to_owned(), 1), ("bob".to_owned(), 2)]),
+        );
+        state.reload = false;
+        state.grid_frame(Some(roster)).unwrap().unwrap();
+        state
+    }
+
+    fn decode(frame: &[u8]) -> GameMessage<'_> {
+        GameMessage::deserialize(frame).unwrap()
+    }
+
+    #[test]
+    fn sends_a_snapshot_after_reading_the_room() {
+        let mut state = RoomState::new();
+        // Sockets get nothing while `reload` is set, the tick reading the room sends the snapshot
+        assert!(state.current_snapshot().unwrap().is_none());
+        state.reload = false;
+        let roster = (
+            vec![("alice".to_owned(), 10.0), ("carol".to_owned(), 0.0)],
+            HashMap::from([("alice".to_owned(), 1), ("carol".to_owned(), 3)]),
+        );
+        let frame = state.grid_frame(Some(roster)).unwrap().unwrap();
+        let message = decode(&frame);
+        assert_eq!(message.clear, Some(true));
+        let mut players = message
+            .players
+            .unwrap()
+            .iter()
+            .map(|player| (player.id, player.name, player.value))
+            .collect::<Vec<_>>();
+        players.sort_unstable();
+        assert_eq!(players, [(1, "alice", 10), (3, "carol", 0)]);
+        // The snapshot had everything, nothing is left for the next tick
+        assert!(!state.has_delta());
+        assert!(state.grid_frame(None).unwrap().is_none());
+        assert!(state.current_snapshot().unwrap().is_some());
+    }
+
+    #[test]
+    fn sends_only_what_changed() {
+        let mut state = loaded();
+        assert!(state.apply(Channel::ScoreUpdates, "25 alice"));
+        // Players that aren't in the room are left out
+        assert!(state.apply(Channel::ScoreUpdates, "30 nobody"));
+        let frame = state.grid_frame(None).unwrap().unwrap();
+        let message = decode(&frame);
+        assert_eq!(message.clear, None);
+        assert!(message.players.is_none());
+        assert_eq!(message.delta_ids.unwrap().iter().collect::<Vec<_>>(), [1]);
+        assert_eq!(
+            message.delta_values.unwrap().iter().collect::<Vec<_>>(),
+            [25]
+        );
+        assert!(message.joined.unwrap().is_empty());
+        assert!(message.left.unwrap().is_empty());
+        assert!(state.grid_frame(None).unwrap().is_none());
+    }
+
+    #[test]
+    fn announces_joins_and_leaves_by_id() {
+        let mut state = loaded();
+        assert!(state.apply(Channel::Joins, "3 0 carol"));
+        assert!(state.apply(Channel::Leaves, "2 bob"));
+        // A leave for an id that isn't the player's anymore is stale
+        assert!(state.apply(Channel::Leaves, "7 alice"));
+        let frame = state.grid_frame(None).unwrap().unwrap();
+        let message = decode(&frame);
+        let joined = message.joined.unwrap();
+        assert_eq!(joined.len(), 1);
+        assert_eq!(
+            (joined[0].id, joined[0].name, joined[0].value),
+            (Some(3), Some("carol"), Some(0))
+        );
+        let left = message.left.unwrap();
+        assert_eq!(
+            left.iter().map(|left| left.id).collect::<Vec<_>>(),
+            [Some(2)]
+        );
+        assert!(state.players.contains_key("alice"));
+        assert!(!state.players.contains_key("bob"));
+    }
+
+    #[test]
+    fn replaces_a_name_taken_over_by_a_new_player() {
+        let mut state = loaded();
+        assert!(state.apply(Channel::Joins, "5 0 alice"));
+        let frame = state.grid_frame(None).unwrap().unwrap();
+        let message = decode(&frame);
+        assert_eq!(message.left.unwrap()[0].id, Some(1));
+        assert_eq!(message.joined.unwrap()[0].id, Some(5));
+    }
+
+    #[test]
+    fn drops_the_join_of_a_player_that_left_in_the_same_tick() {
+        let mut state = loaded();
+        state.apply(Channel::Joins, "3 0 carol");
+        state.apply(Channel::ScoreUpdates, "5 carol");
+        state.apply(Channel::Leaves, "3 carol");
+        let frame = state.grid_frame(None).unwrap().unwrap();
+        let message = decode(&frame);
+        assert!(message.joined.unwrap().is_empty());
+        assert!(message.delta_ids.unwrap().is_empty());
+        assert_eq!(message.left.unwrap()[0].id, Some(3));
+    }
+
+    #[test]
+    fn rejects_malformed_payloads() {
+        let mut state = loaded();
+        assert!(!state.apply(Channel::Joins, "3 carol"));
+        assert!(!state.apply(Channel::Joins, "x 0 carol"));
+        assert!(!state.apply(Channel::Leaves, "bob"));
+        assert!(!state.apply(Channel::ScoreUpdates, "many alice"));
+        assert!(!state.has_delta());
+    }
+
+    #[test]
+    fn sends_the_leaderboard_only_when_watched_and_changed() {
+        let mut state = loaded();
+        // Stays dirty until someone watches it
+        assert!(!state.take_leaderboard_dirty());
+        let _watcher = state.leaderboard.subscribe();
+        assert!(state.take_leaderboard_dirty());
+        assert!(!state.take_leaderboard_dirty());
+
+        state.apply(Channel::ScoreUpdates, "30 nobody");
+        assert!(!state.take_leaderboard_dirty());
+        state.apply(Channel::ScoreUpdates, "30 bob");
+        assert!(state.take_leaderboard_dirty());
+        assert!(!state.take_leaderboard_dirty());
+    }
+}
@@ -1,34 +1,54 @@
-use std::{collections::HashSet, net::SocketAddr, time::Duration};
+use std::{collections::HashSet, net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
 use axum::{
     Router,
     extract::{
-        State, WebSocketUpgrade,
+        Path, State, WebSocketUpgrade,
         ws::{Message, WebSocket},
     },
     http::StatusCode,
     response::Response,
     routing::get,
 };
-use bebop::Record;
-use clap::{Parser, Subcommand};
+use bebop::{Record, SliceWrapper};
+use clap::{Parser, Subcommand, ValueEnum};
 use clap_verbosity_flag::InfoLevel;
 use color_eyre::eyre::{self, Context, OptionExt, eyre};
-use redis::{AsyncConnectionConfig, AsyncTypedCommands, Client, PushInfo, Value};
-use tokio::{
-    net::TcpListener,
-    signal,
-    time::{self, Instant},
-};
+use redis::{Client, aio::ConnectionManager};
+use serde::{Deserialize, Serialize};
+use tokio::{net::TcpListener, signal};
 use tower_http::{catch_panic::CatchPanicLayer, services::ServeDir};
+use tracing::Instrument;
 use tracing::level_filters::LevelFilter;
 use tracing_error::ErrorLayer;
-use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt};
 
-use crate::error::WithStatusCode;
+use crate::{
+    capabilities::Capabilities,
+    config::Config,
+    error::WithStatusCode,
+    hub::Hub,
+    messages::redis_game::{GameMessage, Hello, PROTOCOL_VERSION, PowerupState, Role, Welcome},
+    metrics::{LeaveLabels, METRICS},
+    names::{NameChars, NameRules},
+    room::{ClickLimit, Room},
+    rules::GameRules,
+};
 
+mod capabilities;
+mod config;
 mod error;
+mod health;
+mod hub;
 mod messages;
+mod metrics;
+mod names;
+mod powerup;
+mod room;
+mod round;
+mod rules;
+mod scripts;
+mod telemetry;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -38,15 +58,159 @@ struct Cli {
     command: Option<Command>,
     #[command(flatten)]
     verbose: clap_verbosity_flag::Verbosity<InfoLevel>,
-    #[arg(
-        short,
-        long,
-        env = "REDIS_URL",
-        default_value = "redis://localhost?protocol=resp3"
-    )]
-    redis_url: String,
-    #[arg(short, long, env = "LISTEN_ADDR", default_value = "[::]:3000")]
-    listen_addr: SocketAddr,
+    /// TOML file with the settings below and a few more, flags and environment variables win over it
+    #[arg(short, long, env = "CONFIG_FILE")]
+    config: Option<PathBuf>,
+    /// How log lines are written, `json` is meant for log stores [default: full]
+    #[arg(long, env = "LOG_FORMAT", value_enum)]
+    log_format: Option<LogFormat>,
+    /// Redis keeping the game state [default: redis://localhost?protocol=resp3]
+    #[arg(short, long, env = "REDIS_URL")]
+    redis_url: Option<String>,
+    /// Address the game is served on [default: [::]:3000]
+    #[arg(short, long, env = "LISTEN_ADDR")]
+    listen_addr: Option<SocketAddr>,
+    /// Keep the existing game state instead of resetting every room on startup
+    #[arg(long, env = "NO_RESET")]
+    no_reset: bool,
+    /// How many of the top players are sent to leaderboard clients [default: 20]
+    #[arg(long, env = "LEADERBOARD_SIZE")]
+    leaderboard_size: Option<u32>,
+    /// How many clicks a second a player can keep up, extra clicks are dropped [default: 20]
+    #[arg(long, env = "CLICKS_PER_SECOND")]
+    clicks_per_second: Option<u32>,
+    /// How many clicks a player can make at once before being limited to `clicks_per_second` [default: 40]
+    #[arg(long, env = "CLICK_BURST")]
+    click_burst: Option<u32>,
+    /// How many cells a single click can hit with a powerup, the rest are dropped [default: 64]
+    #[arg(long, env = "MAX_CLICK_CELLS")]
+    max_click_cells: Option<u32>,
+    /// Seconds a player whose connection dropped has to reconnect before they're removed [default: 30]
+    #[arg(long, env = "GRACE_PERIOD")]
+    grace_period: Option<u64>,
+    /// Comma separated names that always join as hosts, players can't use them
+    #[arg(long, env = "HOST_NAMES", value_delimiter = ',')]
+    host_names: Option<Vec<String>>,
+    /// Lets anyone who knows it join as a host under any name
+    #[arg(long, env = "HOST_TOKEN", hide_env_values = true)]
+    host_token: Option<String>,
+    /// Fewest characters a player's name can have [default: 1]
+    #[arg(long, env = "NAME_MIN_LEN")]
+    name_min_len: Option<usize>,
+    /// Most characters a player's name can have [default: 24]
+    #[arg(long, env = "NAME_MAX_LEN")]
+    name_max_len: Option<usize>,
+    /// Comma separated kinds of characters names can be made of [default: letters,digits,spaces,punctuation]
+    #[arg(long, env = "NAME_CHARS", value_delimiter = ',')]
+    name_chars: Option<Vec<NameChars>>,
+    /// File with one word per line that can't be used in names
+    #[arg(long, env = "NAME_BLOCKLIST")]
+    name_blocklist: Option<PathBuf>,
+    /// Send clients the full report of errors instead of only an id to find it in the logs
+    #[arg(long, env = "EXPOSE_ERRORS")]
+    expose_errors: bool,
+    /// Base URL of an OpenTelemetry collector to export spans to over OTLP/HTTP,
+    /// e.g. `http://localhost:4318`
+    #[arg(long, env = "OTEL_EXPORTER_OTLP_ENDPOINT")]
+    otlp_endpoint: Option<String>,
+    /// Name of this replica in traces, the pod's name on Kubernetes [default: redis-game]
+    #[arg(long, env = "HOSTNAME")]
+    replica: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum LogFormat {
+    /// Human readable, one line per event
+    Full,
+    /// Like `full` but shorter
+    Compact,
+    /// Human readable over several lines per event
+    Pretty,
+    /// One JSON object per event, with the fields of its spans
+    Json,
+}
+
+impl Cli {
+    /// Lays the flags and environment variables that were set over `config`
+    fn apply(&self, config: &mut Config) {
+        fn set<T: Clone>(setting: &mut T, flag: &Option<T>) {
+            if let Some(flag) = flag {
+                *setting = flag.clone();
+            }
+        }
+
+        set(&mut config.server.log_format, &self.log_format);
+        set(&mut config.server.listen_addr, &self.listen_addr);
+        // Switches can only turn things on over the file
+        config.server.expose_errors |= self.expose_errors;
+        if self.otlp_endpoint.is_some() {
+            config.server.otlp_endpoint.clone_from(&self.otlp_endpoint);
+        }
+        set(&mut config.server.replica, &self.replica);
+
+        set(&mut config.redis.url, &self.redis_url);
+        config.redis.no_reset |= self.no_reset;
+
+        let game = &mut config.game;
+        set(&mut game.leaderboard_size, &self.leaderboard_size);
+        set(&mut game.clicks_per_second, &self.clicks_per_second);
+        set(&mut game.click_burst, &self.click_burst);
+        set(&mut game.max_click_cells, &self.max_click_cells);
+        set(&mut game.grace_period_secs, &self.grace_period);
+
+        let rooms = &mut config.rooms;
+        set(&mut rooms.host_names, &self.host_names);
+        if self.host_token.is_some() {
+            rooms.host_token.clone_from(&self.host_token);
+        }
+        set(&mut rooms.names.min_len, &self.name_min_len);
+        set(&mut rooms.names.max_len, &self.name_max_len);
+        set(&mut rooms.names.chars, &self.name_chars);
+        if self.name_blocklist.is_some() {
+            rooms.names.blocklist.clone_from(&self.name_blocklist);
+        }
+    }
+}
+
+#[derive(Clone)]
+struct AppState {
+    hub: Arc<Hub>,
+    click_limit: ClickLimit,
+    hosts: Arc<Hosts>,
+    grace_period: Duration,
+    names: Arc<NameRules>,
+    replica: Arc<str>,
+    /// Joined by clients that don't name a room
+    default_room: Room,
+    rules: GameRules,
+}
+
+/// Who may join as a host to show the leaderboard
+struct Hosts {
+    names: HashSet<String>,
+    token: Option<String>,
+}
+
+impl Hosts {
+    /// Host names are normalised like the names players join with, or they'd never match
+    fn new(host_names: impl IntoIterator<Item = String>, token: Option<String>) -> Self {
+        Self {
+            names: host_names
+                .into_iter()
+                .map(|name| names::normalise(&name))
+                .collect(),
+            token,
+        }
+    }
+
+    fn allows(&self, name: &str, token: Option<&str>) -> bool {
+        self.names.contains(name)
+            || self
+                .token
+                .as_deref()
+                .is_some_and(|expected| !expected.is_empty() && Some(expected) == token)
+    }
 }
 
 #[derive(Subcommand)]
@@ -54,20 +218,71 @@ enum Command {
     Populate {
         #[clap(default_value = "40")]
         num: u32,
+        /// The default room unless set
+        #[arg(long)]
+        room: Option<Room>,
+    },
+    /// Delete the game's keys, leaving the rest of the database alone
+    Reset {
+        /// Only reset this room instead of every room
+        #[arg(long)]
+        room: Option<Room>,
+    },
+    /// Work with the configuration file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Validate the configuration and print it with the flags and environment variables applied
+    Check,
+}
+
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
     let cli = Cli::parse();
+    let mut config = Config::load(cli.config.as_deref())?;
+    cli.apply(&mut config);
 
-    color_eyre::install()?;
+    if config.server.log_format == LogFormat::Json {
+        // Log stores don't render colors, their escape codes would only get in the way
+        color_eyre::config::HookBuilder::default()
+            .theme(color_eyre::config::Theme::new())
+            .install()?;
+    } else {
+        color_eyre::install()?;
+    }
+    config.check().wrap_err("Invalid configuration")?;
+    error::expose_errors(config.server.expose_errors);
 
-    let redis_client = Client::open(cli.redis_url.as_str()).unwrap();
+    // Only ever shown with the password left out
+    let redis_url = config::redact_password(&config.redis.url);
+    let redis_client = Client::open(config.redis.url.as_str())
+        .wrap_err_with(|| format!("Failed to open redis client at `{}`", redis_url))?;
 
+    // Kept until main returns so the last spans get flushed
+    let (otlp, _telemetry) = match &config.server.otlp_endpoint {
+        Some(endpoint) => {
+            let (layer, telemetry) = telemetry::layer(endpoint, &config.server.replica)?;
+            (Some(layer), Some(telemetry))
+        }
+        None => (None, None),
+    };
+
+    let fmt = tracing_subscriber::fmt::layer();
+    let fmt = match config.server.log_format {
+        LogFormat::Full => fmt.boxed(),
+        LogFormat::Compact => fmt.compact().boxed(),
+        LogFormat::Pretty => fmt.pretty().boxed(),
+        LogFormat::Json => fmt.json().boxed(),
+    };
     let registry = tracing_subscriber::registry()
+        .with(otlp)
         .with(ErrorLayer::default())
-        .with(tracing_subscriber::fmt::layer());
+        .with(fmt);
 
     if cli.verbose.is_present() {
         registry.with(LevelFilter::from(cli.verbose)).init()
@@ -81,252 +296,687 @@ async fn main() -> eyre::Result<()> {
             .init()
     }
 
+    let rules = config.rules();
     match cli.command {
-        Some(Command::Populate { num }) => {
+        Some(Command::Populate { num, room }) => {
+            let room = match room {
+                Some(room) => room,
+                None => config.default_room()?,
+            };
             let mut connection = redis_client
-                .get_multiplexed_async_connection()
+                .get_connection_manager()
                 .await
-                .wrap_err_with(|| {
-                    format!("Failed to open redis connection at `{}`", cli.redis_url)
-                })?;
+                .wrap_err_with(|| format!("Failed to open redis connection at `{}`", redis_url))?;
 
             for _ in 0..num {
                 let id = nanoid::nanoid!();
 
-                connection
-                    .set(&id, 50_000)
-                    .await
-                    .wrap_err("Failed to set key for random player")?;
-
-                connection
-                    .publish("joins", id)
-                    .await
-                    .wrap_err("Failed to publish join for random player")?;
+                room::join(
+                    &mut connection,
+                    &room,
+                    &id,
+                    rules.populate_score,
+                    &id,
+                    &nanoid::nanoid!(),
+                )
+                .await
+                .wrap_err("Failed to register random player")?
+                .ok_or_eyre("Random player's name was already taken")?;
             }
 
             Ok(())
         }
-        None => {
-            redis_client
-                .get_multiplexed_async_connection()
+        Some(Command::Reset { room }) => {
+            let mut connection = redis_client
+                .get_connection_manager()
                 .await
-                .wrap_err("Failed to open redis connection")?
-                .flushall()
+                .wrap_err_with(|| format!("Failed to open redis connection at `{}`", redis_url))?;
+
+            let deleted = match room {
+                Some(room) => room::reset(&mut connection, &room.pattern()).await?,
+                None => room::reset(&mut connection, room::ALL_ROOMS_PATTERN).await?,
+            };
+            tracing::info!("Deleted {} keys", deleted);
+
+            Ok(())
+        }
+        Some(Command::Config {
+            command: ConfigCommand::Check,
+        }) => {
+            print!("{}", config.to_toml()?);
+            Ok(())
+        }
+        None => {
+            if !config.redis.no_reset {
+                let mut connection = redis_client
+                    .get_connection_manager()
+                    .await
+                    .wrap_err("Failed to open redis connection")?;
+                let deleted = room::reset(&mut connection, room::ALL_ROOMS_PATTERN)
+                    .await
+                    .wrap_err("Failed to reset the game")?;
+                tracing::info!("Reset the game, deleted {} keys", deleted);
+            }
+
+            let game = &config.game;
+            let rooms = config.rooms.clone();
+            let blocklist = match &rooms.names.blocklist {
+                Some(path) => NameRules::load_blocklist(path)?,
+                None => HashSet::new(),
+            };
+
+            let (hub, hub_task) = Hub::new(&redis_client, game.leaderboard_size, rules)
                 .await
-                .wrap_err("Failed to flushall the database")?;
+                .wrap_err("Failed to start the hub")?;
 
             let app = Router::new()
+                .route("/healthz", get(health::healthz))
+                .route("/readyz", get(health::readyz))
+                .route("/metrics", get(metrics::serve))
                 .route("/redis-game/ws", get(game_server))
+                .route("/redis-game/ws/{room}", get(game_server))
                 .fallback_service(
                     ServeDir::new("dist")
                         .precompressed_gzip()
                         .precompressed_br(),
                 )
                 .layer(CatchPanicLayer::custom(error::PanicHandler))
-                .with_state(redis_client);
+                .layer(axum::middleware::from_fn(error::negotiate))
+                .with_state(AppState {
+                    hub,
+                    click_limit: ClickLimit {
+                        per_second: game.clicks_per_second,
+                        burst: game.click_burst,
+                        max_cells: game.max_click_cells,
+                    },
+                    hosts: Arc::new(Hosts::new(rooms.host_names, rooms.host_token)),
+                    grace_period: Duration::from_secs(game.grace_period_secs),
+                    names: Arc::new(NameRules {
+                        min_len: rooms.names.min_len,
+                        max_len: rooms.names.max_len,
+                        chars: rooms.names.chars,
+                        blocklist,
+                    }),
+                    replica: config.server.replica.as_str().into(),
+                    default_room: config.default_room()?,
+                    rules,
+                });
 
-            let listener = TcpListener::bind(cli.listen_addr)
+            let listen_addr = config.server.listen_addr;
+            let listener = TcpListener::bind(listen_addr)
                 .await
-                .wrap_err_with(|| format!("Failed to open listener on {}", cli.listen_addr))?;
-            tracing::info!("Listening on {}", cli.listen_addr);
-            axum::serve(listener, app.into_make_service())
-                .with_graceful_shutdown(shutdown_signal())
-                .await
-                .wrap_err("Failed to serve make service")
+                .wrap_err_with(|| format!("Failed to open listener on {}", listen_addr))?;
+            tracing::info!("Listening on {}", listen_addr);
+            let serve = axum::serve(listener, app.into_make_service())
+                .with_graceful_shutdown(shutdown_signal());
+            // Without the hub no socket would get updates, so it failing takes the server down
+            tokio::select! {
+                result = serve => result.wrap_err("Failed to serve make service"),
+                result = hub_task => result.wrap_err("The hub stopped"),
+            }
         }
     }
 }
 
 async fn game_server(
-    State(pool): State<Client>,
+    State(state): State<AppState>,
+    room: Option<Path<String>>,
     ws: WebSocketUpgrade,
 ) -> Result<Response, error::Error> {
-    Ok(ws.on_upgrade(move |mut socket| async move {
-        if let Err(e) = handle_socket(&mut socket, pool).await {
-            tracing::error!(?e);
-            let _ = socket.send(Message::Text(format!("{}", e).into())).await;
+    let room = match room {
+        Some(Path(room)) => Room::new(room)?,
+        None => state.default_room.clone(),
+    };
+
+    // Tells this connection apart from any other using the same name
+    let session = nanoid::nanoid!();
+    // Everything a connection does, down to its Redis commands, is traced and logged under this span
+    let span = tracing::info_span!(
+        "socket",
+        %room,
+        session,
+        replica = %state.replica,
+        name = tracing::field::Empty,
+        role = tracing::field::Empty,
+    );
+    Ok(ws.on_upgrade(move |mut socket| {
+        async move {
+            METRICS.sockets.inc();
+            tracing::debug!("Client connected");
+            let mut stage = Stage::Handshake;
+            if let Err(e) = handle_socket(&mut socket, state, room, &session, &mut stage).await {
+                let _ = send_error(&mut socket, stage, &e).await;
+            }
+            METRICS.sockets.dec();
         }
+        .instrument(span)
     }))
 }
 
-async fn handle_socket(socket: &mut WebSocket, pool: Client) -> Result<(), error::Error> {
-    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
-    let config = AsyncConnectionConfig::new().set_push_sender(tx);
+/// First protocol version whose pages read errors from the `Welcome`
+const WELCOME_ERROR_VERSION: u32 = 7;
+
+/// How far a client got, decides which frame it expects an error in
+#[derive(Clone, Copy)]
+enum Stage {
+    /// Sent its name as text or an old hello, pages from before errors had a
+    /// code show them as HTML and would take a `Welcome` with an error for a welcome
+    Legacy,
+    /// Said hello and waits for a `Welcome`
+    Handshake,
+    /// Was welcomed, every frame is a `GameMessage`
+    Playing,
+}
 
-    let mut db = pool
-        .get_multiplexed_async_connection_with_config(&config)
+/// Tells the client why the server is ending the connection
+async fn send_error(
+    socket: &mut WebSocket,
+    stage: Stage,
+    error: &error::Error,
+) -> eyre::Result<()> {
+    let error = error.publish();
+    let frame = match stage {
+        Stage::Legacy => Message::Text(error.page.into()),
+        Stage::Handshake => {
+            let mut welcome = Vec::new();
+            Welcome {
+                error: Some(error.to_message()),
+                ..Default::default()
+            }
+            .serialize(&mut welcome)
+            .wrap_err("Failed to serialize welcome message")?;
+            Message::Binary(welcome.into())
+        }
+        Stage::Playing => Message::Binary(hub::serialize(GameMessage {
+            error: Some(error.to_message()),
+            ..Default::default()
+        })?),
+    };
+    socket
+        .send(frame)
         .await
-        .wrap_err("Failed to get connection to Redis")
-        .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+        .wrap_err("Failed to send error message")
+}
+
+async fn handle_socket(
+    socket: &mut WebSocket,
+    state: AppState,
+    room: Room,
+    session: &str,
+    stage: &mut Stage,
+) -> Result<(), error::Error> {
+    let mut db = state.hub.connection();
 
-    let Message::Text(name) = socket
+    let Message::Binary(hello) = socket
         .recv()
         .await
-        .ok_or_eyre("Did not receive any name message")
+        .ok_or_eyre("Did not receive any hello message")
         .with_status_code(StatusCode::BAD_REQUEST)?
-        .wrap_err("Failed to receive name message")
+        .wrap_err("Failed to receive hello message")
         .with_status_code(StatusCode::BAD_REQUEST)?
     else {
-        return Err(eyre!("Name message was not a text message"))
-            .with_status_code(StatusCode::BAD_REQUEST);
+        // Clients from before the handshake send their name as text
+        *stage = Stage::Legacy;
+        return Err(eyre!("This page is out of date, reload it to keep playing"))
+            .with_status_code(StatusCode::UPGRADE_REQUIRED);
+    };
+    let hello = Hello::deserialize(&hello)
+        .wrap_err("Failed to parse hello message")
+        .with_status_code(StatusCode::BAD_REQUEST)?;
+    if hello
+        .protocol_version
+        .is_none_or(|version| version < WELCOME_ERROR_VERSION)
+    {
+        *stage = Stage::Legacy;
+    }
+    if hello.protocol_version != Some(PROTOCOL_VERSION) {
+        return Err(eyre!(
+            "This page speaks protocol version {} but the server needs version {}, reload it to keep playing",
+            hello.protocol_version.unwrap_or_default(),
+            PROTOCOL_VERSION
+        ))
+        .with_status_code(StatusCode::UPGRADE_REQUIRED);
+    }
+    let name = hello
+        .client_name
+        .ok_or_eyre("Hello message did not include a name")
+        .with_status_code(StatusCode::BAD_REQUEST)?;
+    let name = match state.names.check(name) {
+        Ok(name) => name,
+        Err(e) => return reject_name(socket, &room, &e.to_string()).await,
     };
+    tracing::Span::current().record("name", &name);
+    let capabilities = Capabilities::negotiate(hello.capabilities.into_iter().flatten());
+    tracing::debug!(?capabilities, "Client said hello");
 
-    let bradshaw = name == "Bradshaw" || name == "Diaz";
+    // Host names are reserved, so whoever uses one is a host
+    let role = if state.hosts.names.contains(&name) {
+        Role::Host
+    } else {
+        hello.role.unwrap_or(Role::Player)
+    };
+    if role == Role::Host && !state.hosts.allows(&name, hello.host_token) {
+        return Err(eyre!("Joining as a host needs the host token"))
+            .with_status_code(StatusCode::FORBIDDEN);
+    }
+    let host = role == Role::Host;
+    tracing::Span::current().record("role", tracing::field::debug(role));
 
-    if !bradshaw {
-        db.set(name.as_str(), 0i32)
+    // Proves a later connection is this player's, unlike the session it's never logged
+    let resume_token = nanoid::nanoid!();
+    let player_id = if host {
+        None
+    } else {
+        // Frees up the names of players who didn't come back in time
+        room::reap(&mut db, &room)
             .await
-            .wrap_err("Failed to set initial name key")
+            .wrap_err("Failed to reap disconnected players")
             .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
-    }
+        let resumed = match hello.resume_token {
+            Some(token) if capabilities.resume => {
+                room::resume(&mut db, &room, &name, token, session, &resume_token)
+                    .await
+                    .wrap_err("Failed to resume player")
+                    .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?
+            }
+            _ => None,
+        };
+        match resumed {
+            Some(id) => {
+                tracing::info!(player_id = id, "Player resumed");
+                METRICS.resumes.inc();
+                Some(id)
+            }
+            // Too late to resume, they start over as a new player
+            None => match room::join(
+                &mut db,
+                &room,
+                &name,
+                state.rules.start_score,
+                session,
+                &resume_token,
+            )
+            .await
+            .wrap_err("Failed to register player")
+            .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?
+            {
+                Some(id) => {
+                    tracing::info!(player_id = id, "Player joined");
+                    METRICS.joins.inc();
+                    Some(id)
+                }
+                None => {
+                    let reason = format!("Someone is already playing as `{}` in this room", name);
+                    return reject_name(socket, &room, &reason).await;
+                }
+            },
+        }
+    };
 
-    let mut keys_to_watch_set = HashSet::new();
-    let mut keys_to_watch = Vec::new();
+    // The first thing `play` sends is the welcome
+    *stage = Stage::Playing;
+    if host {
+        tracing::info!("Host joined");
+    }
+    // Hosts have nothing to resume
+    let resume_token = (!host && capabilities.resume).then_some(resume_token.as_str());
+    let result = play(
+        socket,
+        &state,
+        &room,
+        resume_token,
+        role,
+        player_id,
+        capabilities,
+    )
+    .await;
 
-    {
-        let mut scan: redis::AsyncIter<'_, String> = db
-            .scan()
-            .await
-            .wrap_err("Failed to open scan on Redis connection")
-            .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
-        while let Some(key) = scan.next_item().await {
-            let key = key
-                .wrap_err("Failed to get key from Redis scan")
+    if !host {
+        // Kicked players, players who closed the page and clients
+        // that can't resume are gone for good
+        let gone = match &result {
+            Ok(Departure::Left) => true,
+            Ok(Departure::Dropped) => !capabilities.resume,
+            Err(e) => e.0.is_client_error(),
+        };
+        if gone {
+            room::leave(&mut db, &room, &name, session)
+                .await
+                .wrap_err("Failed to unregister player")
+                .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+            tracing::info!("Player left");
+            METRICS
+                .leaves
+                .get_or_create(&LeaveLabels { reason: "left" })
+                .inc();
+        } else {
+            room::suspend(&mut db, &room, &name, session, state.grace_period)
+                .await
+                .wrap_err("Failed to suspend player")
                 .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
-            keys_to_watch_set.insert(key.clone());
-            keys_to_watch.push(key);
+            tracing::info!(grace_period = ?state.grace_period, "Player dropped, waiting for them to resume");
         }
     }
 
-    if !bradshaw {
-        db.publish("joins", name.as_str())
-            .await
-            .wrap_err("Failed to publish join to join channel")
-            .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+    result.map(|_| ())
+}
+
+/// Tells a client they can't join with the name they picked,
+/// the join window shows `reason` so they can try another one
+async fn reject_name(socket: &mut WebSocket, room: &Room, reason: &str) -> error::Result<()> {
+    tracing::debug!(reason, "Turned down a name");
+    let mut welcome = Vec::new();
+    Welcome {
+        server_version: Some(env!("CARGO_PKG_VERSION")),
+        room: Some(&room.to_string()),
+        name_error: Some(reason),
+        ..Default::default()
     }
-    db.subscribe(&["joins", "leaves"])
+    .serialize(&mut welcome)
+    .wrap_err("Failed to serialize welcome message")
+    .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+    socket
+        .send(Message::Binary(welcome.into()))
         .await
-        .wrap_err("Failed to subscribe to channels")
-        .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+        .wrap_err("Failed to send welcome message")
+        .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// How a client's game came to an end
+enum Departure {
+    /// They closed the page
+    Left,
+    /// Their connection dropped, they might come back
+    Dropped,
+}
 
-    let sleep = time::sleep(Duration::from_millis(50));
-    tokio::pin!(sleep);
+/// Welcomes a client that joined the room and relays the game until they leave
+async fn play(
+    socket: &mut WebSocket,
+    state: &AppState,
+    room: &Room,
+    resume_token: Option<&str>,
+    role: Role,
+    player_id: Option<u32>,
+    capabilities: Capabilities,
+) -> error::Result<Departure> {
+    let mut db = state.hub.connection();
+    let host = role == Role::Host;
+
+    let mut welcome = Vec::new();
+    Welcome {
+        server_version: Some(env!("CARGO_PKG_VERSION")),
+        room: Some(&room.to_string()),
+        player_id,
+        role: Some(role),
+        resume_token,
+        name_error: None,
+        error: None,
+        rules: Some(state.rules.to_message()),
+        capabilities: Some(capabilities.names()),
+    }
+    .serialize(&mut welcome)
+    .wrap_err("Failed to serialize welcome message")
+    .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+    socket
+        .send(Message::Binary(welcome.into()))
+        .await
+        .wrap_err("Failed to send welcome message")
+        .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let mut clear = true;
+    let mut subscription = state.hub.subscribe(room, host);
+    if !host {
+        // A room that is still being read gets its snapshot on the first tick
+        if let Some(snapshot) = state.hub.snapshot(room)? {
+            socket
+                .send(Message::Binary(snapshot))
+                .await
+                .wrap_err("Failed to send binary game message")
+                .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+        }
+    }
+    // A resumed player may still have a powerup active or cooling down
+    if let Some(player_id) = player_id
+        && capabilities.powerups
+    {
+        let grant = powerup::state(&mut db, room, player_id)
+            .await
+            .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+        send_powerup(socket, &grant).await?;
+    }
 
     loop {
         tokio::select! {
-            Some(msg) = socket.recv() => {
-                let msg = if let Ok(msg) = msg {
-                    msg
-                } else {
-                    // client disconnected
-                    break;
+            msg = socket.recv() => {
+                let msg = match msg {
+                    Some(Ok(Message::Binary(msg))) => msg,
+                    Some(Ok(Message::Close(_))) => return Ok(Departure::Left),
+                    Some(Ok(_)) => continue,
+                    Some(Err(_)) | None => return Ok(Departure::Dropped),
                 };
 
-                let Message::Binary(msg) = msg else {
-                    // client disconnected
-                    break;
-                };
-
-                if let Ok(msg) = messages::redis_game::GameMessage::deserialize(&msg) {
-                    if let Some(clicks) = msg.clicks {
-                        for click in clicks {
-                            db.incr(click.key, click.value)
-                                .await
-                                .wrap_err("Failed to increment key on click")
-                                .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
-                        }
+                let msg = match parse_message(&msg, player_id, state.rules.click_points) {
+                    Ok(msg) => msg,
+                    Err(e) => {
+                        tracing::warn!("Kicking client that sent a forged message");
+                        METRICS.rejected_messages.inc();
+                        return Err(e);
                     }
                 };
-            }
-            _ = &mut sleep => {
-                let mut key_values = Vec::new();
-                // We're not the first player to join
-                if !keys_to_watch.is_empty() {
-                    let values = db
-                        .mget_ints(&keys_to_watch)
+                let Some(player_id) = player_id else {
+                    continue;
+                };
+                if msg.activate_powerup {
+                    if !capabilities.powerups {
+                        tracing::warn!("Kicking client that activated a powerup it didn't ask for");
+                        METRICS.rejected_messages.inc();
+                        return Err(eyre!("Powerups weren't agreed on in the handshake"))
+                            .with_status_code(StatusCode::BAD_REQUEST);
+                    }
+                    let grant = powerup::activate(&mut db, room, player_id, &state.rules)
                         .await
-                        .wrap_err("Failed to mget keys")
                         .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
-                    for (key, value) in keys_to_watch.iter().zip(values) {
-                        key_values.push(messages::redis_game::KeyValue {
-                            key,
-                            value: value.unwrap_or(0) as i64,
-                        });
-                    }
+                    send_powerup(socket, &grant).await?;
                 }
-                let mut buf = Vec::new();
-                messages::redis_game::GameMessage {
-                    updates: Some(key_values),
-                    clear: Some(clear),
-                    ..Default::default()
+                if !msg.clicks.is_empty() {
+                    apply_clicks(socket, &mut db, state, room, player_id, msg.clicks).await?;
                 }
-                .serialize(&mut buf)
-                .wrap_err("Failed to serialize game message")
-                .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
-                clear = false;
-                socket.send(Message::Binary(buf.into()))
+            }
+            frame = subscription.recv(&state.hub, room) => {
+                let Some(frame) = frame else {
+                    // The hub is shutting down, the player can resume on another replica
+                    return Ok(Departure::Dropped);
+                };
+                socket.send(Message::Binary(frame?))
                     .await
                     .wrap_err("Failed to send binary game message")
                     .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
-                sleep
-                    .as_mut()
-                    .reset(Instant::now() + Duration::from_millis(50));
-            }
-            Some(msg) = rx.recv() => {
-                match msg {
-                    PushInfo {
-                        kind: redis::PushKind::Message,
-                        mut data,
-                    } => {
-                        if let (Some(Value::BulkString(new_name)), Some(Value::BulkString(channel))) =
-                            (data.pop(), data.pop())
-                        {
-                            match String::from_utf8_lossy(&channel).as_ref() {
-                                "joins" => {
-                                    let new_name = String::from_utf8(new_name)
-                                        .wrap_err("Join name was not valid UTF-8")
-                                        .with_status_code(StatusCode::BAD_REQUEST)?;
-                                    if keys_to_watch_set.insert(new_name.clone()) {
-                                        keys_to_watch.push(new_name);
-                                    }
-                                    clear = true;
-                                }
-                                "leaves" => {
-                                    let name = String::from_utf8(new_name)
-                                        .wrap_err("Join name was not valid UTF-8")
-                                        .with_status_code(StatusCode::BAD_REQUEST)?;
-                                    keys_to_watch_set.remove(&name);
-                                    if let Some(name_pos) =
-                                        keys_to_watch.iter().position(|key| key == &name)
-                                    {
-                                        keys_to_watch.swap_remove(name_pos);
-                                        clear = true;
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                    _ => {}
-                }
             }
         }
     }
+}
+
+/// What a client asked for in one game message
+struct ClientMessage {
+    /// The clicked player ids and the points each click is worth
+    clicks: Vec<(u32, i64)>,
+    activate_powerup: bool,
+}
 
-    if !bradshaw {
-        db.del(name.as_str())
+/// Applies as many of a player's clicks as their token bucket allows, as long
+/// as the clicked cells fit the shape of their powerup and a round is being
+/// played. The rest are dropped and the player is sent the real scores of the
+/// players they tried to click so their prediction doesn't drift.
+async fn apply_clicks(
+    socket: &mut WebSocket,
+    db: &mut ConnectionManager,
+    state: &AppState,
+    room: &Room,
+    player_id: u32,
+    clicks: Vec<(u32, i64)>,
+) -> error::Result<()> {
+    let cells = state.hub.grid_cells(room);
+    // Players who aren't in the grid have no cell to check against the shape,
+    // e.g. while the room is still being read
+    let (mut clicks, unknown): (Vec<_>, Vec<_>) = clicks
+        .into_iter()
+        .partition(|(target, _)| cells.contains_key(target));
+    let clicked_cells: Vec<_> = clicks.iter().map(|(target, _)| cells[target]).collect();
+    // A single cell fits every shape, so only clicks on several cells need the powerup
+    let powerup = if clicks.len() > 1 {
+        powerup::active(db, room, player_id)
             .await
-            .wrap_err("Failed to delete name key")
-            .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
-        db.publish("leaves", name.as_str())
+            .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?
+    } else {
+        None
+    };
+    let fits_shape = powerup::fits_shape(powerup, &clicked_cells);
+    let granted = if clicks.is_empty() {
+        false
+    } else if fits_shape {
+        room::take_click_token(db, room, player_id, state.click_limit)
             .await
-            .wrap_err("Failed to publish name to leaves channel")
+            .wrap_err("Failed to take a click token")
+            .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?
+    } else {
+        // The grid can shift under a click when someone joins, so this isn't always cheating
+        tracing::debug!(player_id, ?powerup, "Clicks don't fit the active powerup");
+        false
+    };
+    // A message is one click however many cells it hits, so only the cells are capped
+    let allowed = if granted {
+        clicks.len().min(state.click_limit.max_cells as usize)
+    } else {
+        0
+    };
+    // Cells cut by the cap are dropped, but the player isn't out of tokens
+    let throttled = fits_shape && !clicks.is_empty() && !granted;
+    let mut dropped: Vec<u32> = unknown
+        .into_iter()
+        .chain(clicks.drain(allowed..))
+        .map(|(target, _)| target)
+        .collect();
+    METRICS.clicks_dropped.inc_by(dropped.len() as u64);
+
+    if !clicks.is_empty() {
+        let missed = room::click(db, room, state.rules.rounds(), &clicks)
+            .await
+            .wrap_err("Failed to apply clicks")
             .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+        if !missed.is_empty() {
+            // They most likely left or the round ended while the click was on its way
+            tracing::debug!(player_id, ?missed, "Clicks didn't count");
+        }
+        METRICS
+            .clicks_applied
+            .inc_by((clicks.len() - missed.len()) as u64);
+        METRICS.clicks_dropped.inc_by(missed.len() as u64);
+        dropped.extend(missed);
+    }
+
+    if dropped.is_empty() {
+        return Ok(());
+    }
+    tracing::debug!(player_id, dropped = dropped.len(), "Dropped clicks");
+    let (ids, values): (Vec<_>, Vec<_>) = room::scores(db, room, &dropped)
+        .await
+        .wrap_err("Failed to get the scores of dropped clicks")
+        .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?
+        .into_iter()
+        .map(|(id, score)| (id, score as i64))
+        .unzip();
+    let frame = hub::serialize(GameMessage {
+        throttled: Some(throttled),
+        delta_ids: Some(SliceWrapper::Cooked(&ids)),
+        delta_values: Some(SliceWrapper::Cooked(&values)),
+        ..Default::default()
+    })
+    .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+    socket
+        .send(Message::Binary(frame))
+        .await
+        .wrap_err("Failed to send binary game message")
+        .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Tells the player which powerup they got, or how long until they can have one
+async fn send_powerup(socket: &mut WebSocket, grant: &powerup::Grant) -> error::Result<()> {
+    let frame = hub::serialize(GameMessage {
+        powerup: Some(PowerupState {
+            kind: grant.kind,
+            active_ms: Some(grant.active_for.as_millis() as u32),
+            ready_in_ms: Some(grant.ready_in.as_millis() as u32),
+        }),
+        ..Default::default()
+    })
+    .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)?;
+    socket
+        .send(Message::Binary(frame))
+        .await
+        .wrap_err("Failed to send binary game message")
+        .with_status_code(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Works out what a client asked for, the points each clicked player gets and
+/// whether to activate a powerup. Clients only say who they clicked and whether
+/// it was an assist, anything that an unmodified client wouldn't send is an error.
+/// Only the cell under the cursor can be an assist, so a message has one at most.
+fn parse_message(
+    msg: &[u8],
+    player_id: Option<u32>,
+    click_points: i64,
+) -> error::Result<ClientMessage> {
+    let msg = GameMessage::deserialize(msg)
+        .wrap_err("Failed to parse game message")
+        .with_status_code(StatusCode::BAD_REQUEST)?;
+    let activate_powerup = msg.activate_powerup == Some(true);
+    let clicks = msg.clicks.unwrap_or_default();
+    if clicks.is_empty() && !activate_powerup {
+        return Ok(ClientMessage {
+            clicks: Vec::new(),
+            activate_powerup,
+        });
     }
+    let Some(player_id) = player_id else {
+        return Err(eyre!("Only players can click or use powerups"))
+            .with_status_code(StatusCode::BAD_REQUEST);
+    };
 
-    Ok(())
+    let mut clicked = HashSet::new();
+    let mut assisted = false;
+    let clicks = clicks
+        .into_iter()
+        .map(|click| {
+            let target = click
+                .id
+                .ok_or_eyre("Click did not include a player id")
+                .with_status_code(StatusCode::BAD_REQUEST)?;
+            // Each cell can only be clicked once per frame
+            if !clicked.insert(target) {
+                return Err(eyre!(
+                    "Clicked player {} more than once in one message",
+                    target
+                ))
+                .with_status_code(StatusCode::BAD_REQUEST);
+            }
+            let assist = click.assist == Some(true);
+            if assist && std::mem::replace(&mut assisted, true) {
+                return Err(eyre!("Assisted more than one player in one message"))
+                    .with_status_code(StatusCode::BAD_REQUEST);
+            }
+            // Only your own cell or an assist adds points
+            let points = if target == player_id || assist {
+                click_points
+            } else {
+                -click_points
+            };
+            Ok((target, points))
+        })
+        .collect::<error::Result<_>>()?;
+    Ok(ClientMessage {
+        clicks,
+        activate_powerup,
+    })
 }
 
 async fn shutdown_signal() {
@@ -352,3 +1002,76 @@ async fn shutdown_signal() {
         _ = terminate => {},
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::redis_game::Click;
+
+    fn message(clicks: &[(Option<u32>, bool)], activate_powerup: bool) -> Vec<u8> {
+        let mut buf = Vec::new();
+        GameMessage {
+            clicks: Some(
+                clicks
+                    .iter()
+                    .map(|&(id, assist)| Click {
+                        id,
+                        assist: Some(assist),
+                    })
+                    .collect(),
+            ),
+            activate_powerup: Some(activate_powerup),
+            ..Default::default()
+        }
+        .serialize(&mut buf)
+        .unwrap();
+        buf
+    }
+
+    fn rejected(msg: &[u8], player_id: Option<u32>) -> bool {
+        parse_message(msg, player_id, 10).is_err_and(|e| e.0 == StatusCode::BAD_REQUEST)
+    }
+
+    #[test]
+    fn normalises_host_names() {
+        let hosts = Hosts::new(
+            ["ｐｒｏｊｅｃｔｏｒ".to_owned(), "cafe\u{301}".to_owned()],
+            None,
+        );
+        assert!(hosts.allows("projector", None));
+        assert!(hosts.allows("café", None));
+        assert!(!hosts.allows("someone", None));
+    }
+
+    #[test]
+    fn decides_the_points_of_clicks() {
+        let msg = message(&[(Some(1), false), (Some(2), false), (Some(3), true)], true);
+        let parsed = parse_message(&msg, Some(1), 10).unwrap();
+        // Only your own cell or an assist adds points
+        assert_eq!(parsed.clicks, [(1, 10), (2, -10), (3, 10)]);
+        assert!(parsed.activate_powerup);
+
+        let parsed = parse_message(&message(&[], false), None, 10).unwrap();
+        assert!(parsed.clicks.is_empty());
+        assert!(!parsed.activate_powerup);
+    }
+
+    #[test]
+    fn kicks_forged_clicks() {
+        // Each cell can only be clicked once per message
+        assert!(rejected(
+            &message(&[(Some(2), false), (Some(2), true)], false),
+            Some(1)
+        ));
+        // A powerup click can't assist everyone it hits
+        assert!(rejected(
+            &message(&[(Some(2), true), (Some(3), true)], false),
+            Some(1)
+        ));
+        assert!(rejected(&message(&[(None, false)], false), Some(1)));
+        // Hosts don't play
+        assert!(rejected(&message(&[(Some(2), false)], false), None));
+        assert!(rejected(&message(&[], true), None));
+        assert!(rejected(&[0xff; 3], Some(1)));
+    }
+}
@@ -20,57 +20,141 @@ use bebop::FixedSized as _;
 use core::convert::TryInto as _;
 use std::io::Write as _;
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct KeyValue<'raw> {
-    pub key: &'raw str,
-    pub value: i64,
+pub const PROTOCOL_VERSION: u32 = 9;
+
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Role {
+    Player = 1,
+    Host = 2,
 }
 
-impl<'raw> ::bebop::SubRecord<'raw> for KeyValue<'raw> {
-    const MIN_SERIALIZED_SIZE: usize =
-        <&'raw str>::MIN_SERIALIZED_SIZE + <i64>::MIN_SERIALIZED_SIZE;
+impl ::core::convert::TryFrom<u32> for Role {
+    type Error = ::bebop::DeserializeError;
+
+    fn try_from(value: u32) -> ::bebop::DeResult<Self> {
+        match value {
+            1 => Ok(Role::Player),
+            2 => Ok(Role::Host),
+            d => Err(::bebop::DeserializeError::InvalidEnumDiscriminator(
+                d.into(),
+            )),
+        }
+    }
+}
+
+impl ::core::convert::From<Role> for u32 {
+    fn from(value: Role) -> Self {
+        match value {
+            Role::Player => 1,
+            Role::Host => 2,
+        }
+    }
+}
+
+impl ::bebop::SubRecord<'_> for Role {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::ENUM_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(::bebop::ENUM_SIZE);
 
     #[inline]
     fn serialized_size(&self) -> usize {
-        self.key.serialized_size() + self.value.serialized_size()
+        ::bebop::ENUM_SIZE
     }
 
-    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
-        Ok(
-            zelf.key._serialize_chained(dest)? +
-            zelf.value._serialize_chained(dest)?
-        )
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        u32::from(zelf)._serialize_chained(dest)
     });
 
-    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
-        let mut i = 0;
-        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
-            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
-            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+    #[inline]
+    fn _deserialize_chained(raw: &[u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let (n, v) = u32::_deserialize_chained(raw)?;
+        Ok((n, v.try_into()?))
+    }
+}
+
+impl ::bebop::FixedSized for Role {
+    const SERIALIZED_SIZE: usize = ::bebop::ENUM_SIZE;
+}
+
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ErrorCode {
+    Internal = 1,
+    BadRequest = 2,
+    OutOfDate = 3,
+    Forbidden = 4,
+    NotFound = 5,
+    Conflict = 6,
+}
+
+impl ::core::convert::TryFrom<u32> for ErrorCode {
+    type Error = ::bebop::DeserializeError;
+
+    fn try_from(value: u32) -> ::bebop::DeResult<Self> {
+        match value {
+            1 => Ok(ErrorCode::Internal),
+            2 => Ok(ErrorCode::BadRequest),
+            3 => Ok(ErrorCode::OutOfDate),
+            4 => Ok(ErrorCode::Forbidden),
+            5 => Ok(ErrorCode::NotFound),
+            6 => Ok(ErrorCode::Conflict),
+            d => Err(::bebop::DeserializeError::InvalidEnumDiscriminator(
+                d.into(),
+            )),
         }
+    }
+}
 
-        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
-        i += read;
-        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
-        i += read;
+impl ::core::convert::From<ErrorCode> for u32 {
+    fn from(value: ErrorCode) -> Self {
+        match value {
+            ErrorCode::Internal => 1,
+            ErrorCode::BadRequest => 2,
+            ErrorCode::OutOfDate => 3,
+            ErrorCode::Forbidden => 4,
+            ErrorCode::NotFound => 5,
+            ErrorCode::Conflict => 6,
+        }
+    }
+}
+
+impl ::bebop::SubRecord<'_> for ErrorCode {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::ENUM_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(::bebop::ENUM_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::ENUM_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        u32::from(zelf)._serialize_chained(dest)
+    });
 
-        Ok((i, Self { key: v0, value: v1 }))
+    #[inline]
+    fn _deserialize_chained(raw: &[u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let (n, v) = u32::_deserialize_chained(raw)?;
+        Ok((n, v.try_into()?))
     }
 }
 
-impl<'raw> ::bebop::Record<'raw> for KeyValue<'raw> {}
+impl ::bebop::FixedSized for ErrorCode {
+    const SERIALIZED_SIZE: usize = ::bebop::ENUM_SIZE;
+}
 
 #[derive(Clone, Debug, PartialEq, Default)]
-pub struct GameMessage<'raw> {
+pub struct ErrorMessage<'raw> {
     /// Field 1
-    pub updates: ::core::option::Option<::std::vec::Vec<KeyValue<'raw>>>,
+    pub code: ::core::option::Option<ErrorCode>,
     /// Field 2
-    pub clicks: ::core::option::Option<::std::vec::Vec<KeyValue<'raw>>>,
+    pub message: ::core::option::Option<&'raw str>,
     /// Field 3
-    pub clear: ::core::option::Option<bool>,
+    pub retry: ::core::option::Option<bool>,
+    /// Field 4
+    pub id: ::core::option::Option<&'raw str>,
 }
 
-impl<'raw> ::bebop::SubRecord<'raw> for GameMessage<'raw> {
+impl<'raw> ::bebop::SubRecord<'raw> for ErrorMessage<'raw> {
     const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
 
     #[inline]
@@ -78,17 +162,22 @@ impl<'raw> ::bebop::SubRecord<'raw> for GameMessage<'raw> {
         ::bebop::LEN_SIZE
             + 1
             + self
-                .updates
+                .code
                 .as_ref()
                 .map(|v| v.serialized_size() + 1)
                 .unwrap_or(0)
             + self
-                .clicks
+                .message
                 .as_ref()
                 .map(|v| v.serialized_size() + 1)
                 .unwrap_or(0)
             + self
-                .clear
+                .retry
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .id
                 .as_ref()
                 .map(|v| v.serialized_size() + 1)
                 .unwrap_or(0)
@@ -97,18 +186,22 @@ impl<'raw> ::bebop::SubRecord<'raw> for GameMessage<'raw> {
     ::bebop::define_serialize_chained!(Self => |zelf, dest| {
         let size = zelf.serialized_size();
         ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
-        if let Some(ref v) = zelf.updates {
+        if let Some(ref v) = zelf.code {
             1u8._serialize_chained(dest)?;
             v._serialize_chained(dest)?;
         }
-        if let Some(ref v) = zelf.clicks {
+        if let Some(ref v) = zelf.message {
             2u8._serialize_chained(dest)?;
             v._serialize_chained(dest)?;
         }
-        if let Some(ref v) = zelf.clear {
+        if let Some(ref v) = zelf.retry {
             3u8._serialize_chained(dest)?;
             v._serialize_chained(dest)?;
         }
+        if let Some(ref v) = zelf.id {
+            4u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
         0u8._serialize_chained(dest)?;
         Ok(size)
     });
@@ -127,9 +220,10 @@ impl<'raw> ::bebop::SubRecord<'raw> for GameMessage<'raw> {
             return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
         }
 
-        let mut _updates = None;
-        let mut _clicks = None;
-        let mut _clear = None;
+        let mut _code = None;
+        let mut _message = None;
+        let mut _retry = None;
+        let mut _id = None;
 
         #[cfg(not(feature = "unchecked"))]
         let mut last = 0;
@@ -152,30 +246,39 @@ impl<'raw> ::bebop::SubRecord<'raw> for GameMessage<'raw> {
                 }
                 1 => {
                     #[cfg(not(feature = "unchecked"))]
-                    if _updates.is_some() {
+                    if _code.is_some() {
                         return Err(::bebop::DeserializeError::DuplicateMessageField);
                     }
                     let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
                     i += read;
-                    _updates = Some(value)
+                    _code = Some(value)
                 }
                 2 => {
                     #[cfg(not(feature = "unchecked"))]
-                    if _clicks.is_some() {
+                    if _message.is_some() {
                         return Err(::bebop::DeserializeError::DuplicateMessageField);
                     }
                     let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
                     i += read;
-                    _clicks = Some(value)
+                    _message = Some(value)
                 }
                 3 => {
                     #[cfg(not(feature = "unchecked"))]
-                    if _clear.is_some() {
+                    if _retry.is_some() {
                         return Err(::bebop::DeserializeError::DuplicateMessageField);
                     }
                     let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
                     i += read;
-                    _clear = Some(value)
+                    _retry = Some(value)
+                }
+                4 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _id.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _id = Some(value)
                 }
                 _ => {
                     i = len;
@@ -192,157 +295,3426 @@ impl<'raw> ::bebop::SubRecord<'raw> for GameMessage<'raw> {
         Ok((
             i,
             Self {
-                updates: _updates,
-                clicks: _clicks,
-                clear: _clear,
+                code: _code,
+                message: _message,
+                retry: _retry,
+                id: _id,
             },
         ))
     }
 }
 
-impl<'raw> ::bebop::Record<'raw> for GameMessage<'raw> {}
+impl<'raw> ::bebop::Record<'raw> for ErrorMessage<'raw> {}
 
-#[cfg(feature = "bebop-owned-all")]
-pub mod owned {
-    #![allow(warnings)]
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Hello<'raw> {
+    /// Field 1
+    pub protocol_version: ::core::option::Option<u32>,
+    /// Field 2
+    pub client_name: ::core::option::Option<&'raw str>,
+    /// Field 3
+    pub capabilities: ::core::option::Option<::std::vec::Vec<&'raw str>>,
+    /// Field 4
+    pub role: ::core::option::Option<Role>,
+    /// Field 5
+    pub host_token: ::core::option::Option<&'raw str>,
+    /// Field 6
+    pub resume_token: ::core::option::Option<&'raw str>,
+}
 
-    use bebop::FixedSized as _;
-    use core::convert::TryInto as _;
-    use std::io::Write as _;
+impl<'raw> ::bebop::SubRecord<'raw> for Hello<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
 
-    #[derive(Clone, Debug, PartialEq)]
-    pub struct KeyValue {
-        pub key: String,
-        pub value: i64,
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::LEN_SIZE
+            + 1
+            + self
+                .protocol_version
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .client_name
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .capabilities
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .role
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .host_token
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .resume_token
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
     }
 
-    impl<'raw> ::core::convert::From<super::KeyValue<'raw>> for KeyValue {
-        fn from(value: super::KeyValue) -> Self {
-            Self {
-                key: value.key.into(),
-                value: value.value,
-            }
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        let size = zelf.serialized_size();
+        ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+        if let Some(ref v) = zelf.protocol_version {
+            1u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
         }
-    }
-
-    impl<'raw> ::bebop::SubRecord<'raw> for KeyValue {
-        const MIN_SERIALIZED_SIZE: usize =
-            <String>::MIN_SERIALIZED_SIZE + <i64>::MIN_SERIALIZED_SIZE;
-
-        #[inline]
-        fn serialized_size(&self) -> usize {
-            self.key.serialized_size() + self.value.serialized_size()
+        if let Some(ref v) = zelf.client_name {
+            2u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
         }
-
-        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
-            Ok(
-                zelf.key._serialize_chained(dest)? +
-                zelf.value._serialize_chained(dest)?
-            )
-        });
-
-        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
-            let mut i = 0;
-            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
-                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
-                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
-            }
-
-            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
-            i += read;
-            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
-            i += read;
-
-            Ok((i, Self { key: v0, value: v1 }))
+        if let Some(ref v) = zelf.capabilities {
+            3u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
         }
-    }
-
-    impl<'raw> ::bebop::Record<'raw> for KeyValue {}
+        if let Some(ref v) = zelf.role {
+            4u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.host_token {
+            5u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.resume_token {
+            6u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        0u8._serialize_chained(dest)?;
+        Ok(size)
+    });
 
-    #[derive(Clone, Debug, PartialEq, Default)]
-    pub struct GameMessage {
-        /// Field 1
-        pub updates: ::core::option::Option<::std::vec::Vec<KeyValue>>,
-        /// Field 2
-        pub clicks: ::core::option::Option<::std::vec::Vec<KeyValue>>,
-        /// Field 3
-        pub clear: ::core::option::Option<bool>,
-    }
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+        i += ::bebop::LEN_SIZE;
 
-    impl<'raw> ::core::convert::From<super::GameMessage<'raw>> for GameMessage {
-        fn from(value: super::GameMessage) -> Self {
-            Self {
-                updates: value
-                    .updates
-                    .map(|value| value.into_iter().map(|value| value.into()).collect()),
-                clicks: value
-                    .clicks
-                    .map(|value| value.into_iter().map(|value| value.into()).collect()),
-                clear: value.clear,
-            }
+        #[cfg(not(feature = "unchecked"))]
+        if len == 0 {
+            return Err(::bebop::DeserializeError::CorruptFrame);
         }
-    }
-
-    impl<'raw> ::bebop::SubRecord<'raw> for GameMessage {
-        const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
 
-        #[inline]
-        fn serialized_size(&self) -> usize {
-            ::bebop::LEN_SIZE
-                + 1
-                + self
-                    .updates
-                    .as_ref()
-                    .map(|v| v.serialized_size() + 1)
-                    .unwrap_or(0)
-                + self
-                    .clicks
-                    .as_ref()
-                    .map(|v| v.serialized_size() + 1)
-                    .unwrap_or(0)
-                + self
-                    .clear
-                    .as_ref()
-                    .map(|v| v.serialized_size() + 1)
-                    .unwrap_or(0)
+        if raw.len() < len {
+            return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
         }
 
-        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
-            let size = zelf.serialized_size();
-            ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
-            if let Some(ref v) = zelf.updates {
-                1u8._serialize_chained(dest)?;
-                v._serialize_chained(dest)?;
-            }
-            if let Some(ref v) = zelf.clicks {
-                2u8._serialize_chained(dest)?;
-                v._serialize_chained(dest)?;
-            }
-            if let Some(ref v) = zelf.clear {
-                3u8._serialize_chained(dest)?;
-                v._serialize_chained(dest)?;
-            }
-            0u8._serialize_chained(dest)?;
-            Ok(size)
-        });
+        let mut _protocol_version = None;
+        let mut _client_name = None;
+        let mut _capabilities = None;
+        let mut _role = None;
+        let mut _host_token = None;
+        let mut _resume_token = None;
 
-        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
-            let mut i = 0;
-            let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
-            i += ::bebop::LEN_SIZE;
+        #[cfg(not(feature = "unchecked"))]
+        let mut last = 0;
 
-            #[cfg(not(feature = "unchecked"))]
-            if len == 0 {
-                return Err(::bebop::DeserializeError::CorruptFrame);
-            }
+        while i < len {
+            let di = raw[i];
 
-            if raw.len() < len {
-                return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+            #[cfg(not(feature = "unchecked"))]
+            if di != 0 {
+                if di < last {
+                    return Err(::bebop::DeserializeError::CorruptFrame);
+                }
+                last = di;
             }
 
-            let mut _updates = None;
-            let mut _clicks = None;
+            i += 1;
+            match di {
+                0 => {
+                    break;
+                }
+                1 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _protocol_version.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _protocol_version = Some(value)
+                }
+                2 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _client_name.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _client_name = Some(value)
+                }
+                3 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _capabilities.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _capabilities = Some(value)
+                }
+                4 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _role.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _role = Some(value)
+                }
+                5 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _host_token.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _host_token = Some(value)
+                }
+                6 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _resume_token.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _resume_token = Some(value)
+                }
+                _ => {
+                    i = len;
+                    break;
+                }
+            }
+        }
+
+        if i != len {
+            debug_assert!(i > len);
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        Ok((
+            i,
+            Self {
+                protocol_version: _protocol_version,
+                client_name: _client_name,
+                capabilities: _capabilities,
+                role: _role,
+                host_token: _host_token,
+                resume_token: _resume_token,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Hello<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Rules {
+    /// Field 1
+    pub click_points: ::core::option::Option<i64>,
+    /// Field 2
+    pub start_score: ::core::option::Option<i64>,
+    /// Field 3
+    pub tick_ms: ::core::option::Option<u32>,
+    /// Field 4
+    pub rounds: ::core::option::Option<bool>,
+    /// Field 5
+    pub powerup_ms: ::core::option::Option<u32>,
+    /// Field 6
+    pub powerup_cooldown_ms: ::core::option::Option<u32>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Rules {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::LEN_SIZE
+            + 1
+            + self
+                .click_points
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .start_score
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .tick_ms
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .rounds
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .powerup_ms
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .powerup_cooldown_ms
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        let size = zelf.serialized_size();
+        ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+        if let Some(ref v) = zelf.click_points {
+            1u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.start_score {
+            2u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.tick_ms {
+            3u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.rounds {
+            4u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.powerup_ms {
+            5u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.powerup_cooldown_ms {
+            6u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        0u8._serialize_chained(dest)?;
+        Ok(size)
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+        i += ::bebop::LEN_SIZE;
+
+        #[cfg(not(feature = "unchecked"))]
+        if len == 0 {
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        if raw.len() < len {
+            return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+        }
+
+        let mut _click_points = None;
+        let mut _start_score = None;
+        let mut _tick_ms = None;
+        let mut _rounds = None;
+        let mut _powerup_ms = None;
+        let mut _powerup_cooldown_ms = None;
+
+        #[cfg(not(feature = "unchecked"))]
+        let mut last = 0;
+
+        while i < len {
+            let di = raw[i];
+
+            #[cfg(not(feature = "unchecked"))]
+            if di != 0 {
+                if di < last {
+                    return Err(::bebop::DeserializeError::CorruptFrame);
+                }
+                last = di;
+            }
+
+            i += 1;
+            match di {
+                0 => {
+                    break;
+                }
+                1 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _click_points.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _click_points = Some(value)
+                }
+                2 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _start_score.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _start_score = Some(value)
+                }
+                3 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _tick_ms.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _tick_ms = Some(value)
+                }
+                4 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _rounds.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _rounds = Some(value)
+                }
+                5 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _powerup_ms.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _powerup_ms = Some(value)
+                }
+                6 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _powerup_cooldown_ms.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _powerup_cooldown_ms = Some(value)
+                }
+                _ => {
+                    i = len;
+                    break;
+                }
+            }
+        }
+
+        if i != len {
+            debug_assert!(i > len);
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        Ok((
+            i,
+            Self {
+                click_points: _click_points,
+                start_score: _start_score,
+                tick_ms: _tick_ms,
+                rounds: _rounds,
+                powerup_ms: _powerup_ms,
+                powerup_cooldown_ms: _powerup_cooldown_ms,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Rules {}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Welcome<'raw> {
+    /// Field 1
+    pub server_version: ::core::option::Option<&'raw str>,
+    /// Field 2
+    pub room: ::core::option::Option<&'raw str>,
+    /// Field 3
+    pub player_id: ::core::option::Option<u32>,
+    /// Field 4
+    pub role: ::core::option::Option<Role>,
+    /// Field 5
+    pub resume_token: ::core::option::Option<&'raw str>,
+    /// Field 6
+    pub name_error: ::core::option::Option<&'raw str>,
+    /// Field 7
+    pub error: ::core::option::Option<ErrorMessage<'raw>>,
+    /// Field 8
+    pub rules: ::core::option::Option<Rules>,
+    /// Field 9
+    pub capabilities: ::core::option::Option<::std::vec::Vec<&'raw str>>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Welcome<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::LEN_SIZE
+            + 1
+            + self
+                .server_version
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .room
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .player_id
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .role
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .resume_token
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .name_error
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .error
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .rules
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .capabilities
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        let size = zelf.serialized_size();
+        ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+        if let Some(ref v) = zelf.server_version {
+            1u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.room {
+            2u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.player_id {
+            3u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.role {
+            4u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.resume_token {
+            5u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.name_error {
+            6u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.error {
+            7u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.rules {
+            8u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.capabilities {
+            9u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        0u8._serialize_chained(dest)?;
+        Ok(size)
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+        i += ::bebop::LEN_SIZE;
+
+        #[cfg(not(feature = "unchecked"))]
+        if len == 0 {
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        if raw.len() < len {
+            return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+        }
+
+        let mut _server_version = None;
+        let mut _room = None;
+        let mut _player_id = None;
+        let mut _role = None;
+        let mut _resume_token = None;
+        let mut _name_error = None;
+        let mut _error = None;
+        let mut _rules = None;
+        let mut _capabilities = None;
+
+        #[cfg(not(feature = "unchecked"))]
+        let mut last = 0;
+
+        while i < len {
+            let di = raw[i];
+
+            #[cfg(not(feature = "unchecked"))]
+            if di != 0 {
+                if di < last {
+                    return Err(::bebop::DeserializeError::CorruptFrame);
+                }
+                last = di;
+            }
+
+            i += 1;
+            match di {
+                0 => {
+                    break;
+                }
+                1 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _server_version.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _server_version = Some(value)
+                }
+                2 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _room.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _room = Some(value)
+                }
+                3 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _player_id.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _player_id = Some(value)
+                }
+                4 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _role.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _role = Some(value)
+                }
+                5 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _resume_token.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _resume_token = Some(value)
+                }
+                6 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _name_error.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _name_error = Some(value)
+                }
+                7 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _error.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _error = Some(value)
+                }
+                8 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _rules.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _rules = Some(value)
+                }
+                9 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _capabilities.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _capabilities = Some(value)
+                }
+                _ => {
+                    i = len;
+                    break;
+                }
+            }
+        }
+
+        if i != len {
+            debug_assert!(i > len);
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        Ok((
+            i,
+            Self {
+                server_version: _server_version,
+                room: _room,
+                player_id: _player_id,
+                role: _role,
+                resume_token: _resume_token,
+                name_error: _name_error,
+                error: _error,
+                rules: _rules,
+                capabilities: _capabilities,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Welcome<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RankedKeyValue<'raw> {
+    pub rank: u32,
+    pub key: &'raw str,
+    pub value: i64,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for RankedKeyValue<'raw> {
+    const MIN_SERIALIZED_SIZE: usize =
+        <u32>::MIN_SERIALIZED_SIZE + <&'raw str>::MIN_SERIALIZED_SIZE + <i64>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.rank.serialized_size() + self.key.serialized_size() + self.value.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.rank._serialize_chained(dest)? +
+            zelf.key._serialize_chained(dest)? +
+            zelf.value._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                rank: v0,
+                key: v1,
+                value: v2,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for RankedKeyValue<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Player<'raw> {
+    pub id: u32,
+    pub name: &'raw str,
+    pub value: i64,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Player<'raw> {
+    const MIN_SERIALIZED_SIZE: usize =
+        <u32>::MIN_SERIALIZED_SIZE + <&'raw str>::MIN_SERIALIZED_SIZE + <i64>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.id.serialized_size() + self.name.serialized_size() + self.value.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.id._serialize_chained(dest)? +
+            zelf.name._serialize_chained(dest)? +
+            zelf.value._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                id: v0,
+                name: v1,
+                value: v2,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Player<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Click {
+    /// Field 1
+    pub id: ::core::option::Option<u32>,
+    /// Field 2
+    pub assist: ::core::option::Option<bool>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Click {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::LEN_SIZE
+            + 1
+            + self
+                .id
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .assist
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        let size = zelf.serialized_size();
+        ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+        if let Some(ref v) = zelf.id {
+            1u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.assist {
+            2u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        0u8._serialize_chained(dest)?;
+        Ok(size)
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+        i += ::bebop::LEN_SIZE;
+
+        #[cfg(not(feature = "unchecked"))]
+        if len == 0 {
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        if raw.len() < len {
+            return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+        }
+
+        let mut _id = None;
+        let mut _assist = None;
+
+        #[cfg(not(feature = "unchecked"))]
+        let mut last = 0;
+
+        while i < len {
+            let di = raw[i];
+
+            #[cfg(not(feature = "unchecked"))]
+            if di != 0 {
+                if di < last {
+                    return Err(::bebop::DeserializeError::CorruptFrame);
+                }
+                last = di;
+            }
+
+            i += 1;
+            match di {
+                0 => {
+                    break;
+                }
+                1 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _id.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _id = Some(value)
+                }
+                2 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _assist.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _assist = Some(value)
+                }
+                _ => {
+                    i = len;
+                    break;
+                }
+            }
+        }
+
+        if i != len {
+            debug_assert!(i > len);
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        Ok((
+            i,
+            Self {
+                id: _id,
+                assist: _assist,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Click {}
+
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PowerupKind {
+    Rows = 1,
+    Columns = 2,
+    RowsColumns = 3,
+    Random = 4,
+    Autoclick = 5,
+}
+
+impl ::core::convert::TryFrom<u32> for PowerupKind {
+    type Error = ::bebop::DeserializeError;
+
+    fn try_from(value: u32) -> ::bebop::DeResult<Self> {
+        match value {
+            1 => Ok(PowerupKind::Rows),
+            2 => Ok(PowerupKind::Columns),
+            3 => Ok(PowerupKind::RowsColumns),
+            4 => Ok(PowerupKind::Random),
+            5 => Ok(PowerupKind::Autoclick),
+            d => Err(::bebop::DeserializeError::InvalidEnumDiscriminator(
+                d.into(),
+            )),
+        }
+    }
+}
+
+impl ::core::convert::From<PowerupKind> for u32 {
+    fn from(value: PowerupKind) -> Self {
+        match value {
+            PowerupKind::Rows => 1,
+            PowerupKind::Columns => 2,
+            PowerupKind::RowsColumns => 3,
+            PowerupKind::Random => 4,
+            PowerupKind::Autoclick => 5,
+        }
+    }
+}
+
+impl ::bebop::SubRecord<'_> for PowerupKind {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::ENUM_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(::bebop::ENUM_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::ENUM_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        u32::from(zelf)._serialize_chained(dest)
+    });
+
+    #[inline]
+    fn _deserialize_chained(raw: &[u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let (n, v) = u32::_deserialize_chained(raw)?;
+        Ok((n, v.try_into()?))
+    }
+}
+
+impl ::bebop::FixedSized for PowerupKind {
+    const SERIALIZED_SIZE: usize = ::bebop::ENUM_SIZE;
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PowerupState {
+    /// Field 1
+    pub kind: ::core::option::Option<PowerupKind>,
+    /// Field 2
+    pub active_ms: ::core::option::Option<u32>,
+    /// Field 3
+    pub ready_in_ms: ::core::option::Option<u32>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for PowerupState {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::LEN_SIZE
+            + 1
+            + self
+                .kind
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .active_ms
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .ready_in_ms
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        let size = zelf.serialized_size();
+        ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+        if let Some(ref v) = zelf.kind {
+            1u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.active_ms {
+            2u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.ready_in_ms {
+            3u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        0u8._serialize_chained(dest)?;
+        Ok(size)
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+        i += ::bebop::LEN_SIZE;
+
+        #[cfg(not(feature = "unchecked"))]
+        if len == 0 {
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        if raw.len() < len {
+            return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+        }
+
+        let mut _kind = None;
+        let mut _active_ms = None;
+        let mut _ready_in_ms = None;
+
+        #[cfg(not(feature = "unchecked"))]
+        let mut last = 0;
+
+        while i < len {
+            let di = raw[i];
+
+            #[cfg(not(feature = "unchecked"))]
+            if di != 0 {
+                if di < last {
+                    return Err(::bebop::DeserializeError::CorruptFrame);
+                }
+                last = di;
+            }
+
+            i += 1;
+            match di {
+                0 => {
+                    break;
+                }
+                1 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _kind.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _kind = Some(value)
+                }
+                2 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _active_ms.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _active_ms = Some(value)
+                }
+                3 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _ready_in_ms.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _ready_in_ms = Some(value)
+                }
+                _ => {
+                    i = len;
+                    break;
+                }
+            }
+        }
+
+        if i != len {
+            debug_assert!(i > len);
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        Ok((
+            i,
+            Self {
+                kind: _kind,
+                active_ms: _active_ms,
+                ready_in_ms: _ready_in_ms,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for PowerupState {}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PlayerJoined<'raw> {
+    /// Field 1
+    pub id: ::core::option::Option<u32>,
+    /// Field 2
+    pub name: ::core::option::Option<&'raw str>,
+    /// Field 3
+    pub value: ::core::option::Option<i64>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for PlayerJoined<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::LEN_SIZE
+            + 1
+            + self
+                .id
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .name
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .value
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        let size = zelf.serialized_size();
+        ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+        if let Some(ref v) = zelf.id {
+            1u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.name {
+            2u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.value {
+            3u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        0u8._serialize_chained(dest)?;
+        Ok(size)
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+        i += ::bebop::LEN_SIZE;
+
+        #[cfg(not(feature = "unchecked"))]
+        if len == 0 {
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        if raw.len() < len {
+            return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+        }
+
+        let mut _id = None;
+        let mut _name = None;
+        let mut _value = None;
+
+        #[cfg(not(feature = "unchecked"))]
+        let mut last = 0;
+
+        while i < len {
+            let di = raw[i];
+
+            #[cfg(not(feature = "unchecked"))]
+            if di != 0 {
+                if di < last {
+                    return Err(::bebop::DeserializeError::CorruptFrame);
+                }
+                last = di;
+            }
+
+            i += 1;
+            match di {
+                0 => {
+                    break;
+                }
+                1 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _id.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _id = Some(value)
+                }
+                2 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _name.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _name = Some(value)
+                }
+                3 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _value.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _value = Some(value)
+                }
+                _ => {
+                    i = len;
+                    break;
+                }
+            }
+        }
+
+        if i != len {
+            debug_assert!(i > len);
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        Ok((
+            i,
+            Self {
+                id: _id,
+                name: _name,
+                value: _value,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for PlayerJoined<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PlayerLeft {
+    /// Field 1
+    pub id: ::core::option::Option<u32>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for PlayerLeft {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::LEN_SIZE
+            + 1
+            + self
+                .id
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        let size = zelf.serialized_size();
+        ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+        if let Some(ref v) = zelf.id {
+            1u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        0u8._serialize_chained(dest)?;
+        Ok(size)
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+        i += ::bebop::LEN_SIZE;
+
+        #[cfg(not(feature = "unchecked"))]
+        if len == 0 {
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        if raw.len() < len {
+            return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+        }
+
+        let mut _id = None;
+
+        #[cfg(not(feature = "unchecked"))]
+        let mut last = 0;
+
+        while i < len {
+            let di = raw[i];
+
+            #[cfg(not(feature = "unchecked"))]
+            if di != 0 {
+                if di < last {
+                    return Err(::bebop::DeserializeError::CorruptFrame);
+                }
+                last = di;
+            }
+
+            i += 1;
+            match di {
+                0 => {
+                    break;
+                }
+                1 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _id.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _id = Some(value)
+                }
+                _ => {
+                    i = len;
+                    break;
+                }
+            }
+        }
+
+        if i != len {
+            debug_assert!(i > len);
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        Ok((i, Self { id: _id }))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for PlayerLeft {}
+
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RoundPhase {
+    Lobby = 1,
+    Countdown = 2,
+    Playing = 3,
+    Results = 4,
+}
+
+impl ::core::convert::TryFrom<u32> for RoundPhase {
+    type Error = ::bebop::DeserializeError;
+
+    fn try_from(value: u32) -> ::bebop::DeResult<Self> {
+        match value {
+            1 => Ok(RoundPhase::Lobby),
+            2 => Ok(RoundPhase::Countdown),
+            3 => Ok(RoundPhase::Playing),
+            4 => Ok(RoundPhase::Results),
+            d => Err(::bebop::DeserializeError::InvalidEnumDiscriminator(
+                d.into(),
+            )),
+        }
+    }
+}
+
+impl ::core::convert::From<RoundPhase> for u32 {
+    fn from(value: RoundPhase) -> Self {
+        match value {
+            RoundPhase::Lobby => 1,
+            RoundPhase::Countdown => 2,
+            RoundPhase::Playing => 3,
+            RoundPhase::Results => 4,
+        }
+    }
+}
+
+impl ::bebop::SubRecord<'_> for RoundPhase {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::ENUM_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(::bebop::ENUM_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::ENUM_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        u32::from(zelf)._serialize_chained(dest)
+    });
+
+    #[inline]
+    fn _deserialize_chained(raw: &[u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let (n, v) = u32::_deserialize_chained(raw)?;
+        Ok((n, v.try_into()?))
+    }
+}
+
+impl ::bebop::FixedSized for RoundPhase {
+    const SERIALIZED_SIZE: usize = ::bebop::ENUM_SIZE;
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct RoundState<'raw> {
+    /// Field 1
+    pub phase: ::core::option::Option<RoundPhase>,
+    /// Field 2
+    pub number: ::core::option::Option<u32>,
+    /// Field 3
+    pub remaining_ms: ::core::option::Option<u32>,
+    /// Field 4
+    pub duration_ms: ::core::option::Option<u32>,
+    /// Field 5
+    pub min_players: ::core::option::Option<u32>,
+    /// Field 6
+    pub podium: ::core::option::Option<::std::vec::Vec<RankedKeyValue<'raw>>>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for RoundState<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::LEN_SIZE
+            + 1
+            + self
+                .phase
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .number
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .remaining_ms
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .duration_ms
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .min_players
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .podium
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        let size = zelf.serialized_size();
+        ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+        if let Some(ref v) = zelf.phase {
+            1u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.number {
+            2u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.remaining_ms {
+            3u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.duration_ms {
+            4u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.min_players {
+            5u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.podium {
+            6u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        0u8._serialize_chained(dest)?;
+        Ok(size)
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+        i += ::bebop::LEN_SIZE;
+
+        #[cfg(not(feature = "unchecked"))]
+        if len == 0 {
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        if raw.len() < len {
+            return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+        }
+
+        let mut _phase = None;
+        let mut _number = None;
+        let mut _remaining_ms = None;
+        let mut _duration_ms = None;
+        let mut _min_players = None;
+        let mut _podium = None;
+
+        #[cfg(not(feature = "unchecked"))]
+        let mut last = 0;
+
+        while i < len {
+            let di = raw[i];
+
+            #[cfg(not(feature = "unchecked"))]
+            if di != 0 {
+                if di < last {
+                    return Err(::bebop::DeserializeError::CorruptFrame);
+                }
+                last = di;
+            }
+
+            i += 1;
+            match di {
+                0 => {
+                    break;
+                }
+                1 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _phase.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _phase = Some(value)
+                }
+                2 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _number.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _number = Some(value)
+                }
+                3 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _remaining_ms.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _remaining_ms = Some(value)
+                }
+                4 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _duration_ms.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _duration_ms = Some(value)
+                }
+                5 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _min_players.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _min_players = Some(value)
+                }
+                6 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _podium.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _podium = Some(value)
+                }
+                _ => {
+                    i = len;
+                    break;
+                }
+            }
+        }
+
+        if i != len {
+            debug_assert!(i > len);
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        Ok((
+            i,
+            Self {
+                phase: _phase,
+                number: _number,
+                remaining_ms: _remaining_ms,
+                duration_ms: _duration_ms,
+                min_players: _min_players,
+                podium: _podium,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for RoundState<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct GameMessage<'raw> {
+    /// Field 2
+    pub clicks: ::core::option::Option<::std::vec::Vec<Click>>,
+    /// Field 3
+    pub clear: ::core::option::Option<bool>,
+    /// Field 4
+    pub leaderboard: ::core::option::Option<::std::vec::Vec<RankedKeyValue<'raw>>>,
+    /// Field 5
+    pub players: ::core::option::Option<::std::vec::Vec<Player<'raw>>>,
+    /// Field 6
+    pub delta_ids: ::core::option::Option<::bebop::SliceWrapper<'raw, u32>>,
+    /// Field 7
+    pub delta_values: ::core::option::Option<::bebop::SliceWrapper<'raw, i64>>,
+    /// Field 8
+    pub joined: ::core::option::Option<::std::vec::Vec<PlayerJoined<'raw>>>,
+    /// Field 9
+    pub left: ::core::option::Option<::std::vec::Vec<PlayerLeft>>,
+    /// Field 10
+    pub throttled: ::core::option::Option<bool>,
+    /// Field 11
+    pub activate_powerup: ::core::option::Option<bool>,
+    /// Field 12
+    pub powerup: ::core::option::Option<PowerupState>,
+    /// Field 13
+    pub error: ::core::option::Option<ErrorMessage<'raw>>,
+    /// Field 14
+    pub round: ::core::option::Option<RoundState<'raw>>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for GameMessage<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        ::bebop::LEN_SIZE
+            + 1
+            + self
+                .clicks
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .clear
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .leaderboard
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .players
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .delta_ids
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .delta_values
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .joined
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .left
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .throttled
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .activate_powerup
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .powerup
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .error
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+            + self
+                .round
+                .as_ref()
+                .map(|v| v.serialized_size() + 1)
+                .unwrap_or(0)
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        let size = zelf.serialized_size();
+        ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+        if let Some(ref v) = zelf.clicks {
+            2u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.clear {
+            3u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.leaderboard {
+            4u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.players {
+            5u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.delta_ids {
+            6u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.delta_values {
+            7u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.joined {
+            8u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.left {
+            9u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.throttled {
+            10u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.activate_powerup {
+            11u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.powerup {
+            12u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.error {
+            13u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        if let Some(ref v) = zelf.round {
+            14u8._serialize_chained(dest)?;
+            v._serialize_chained(dest)?;
+        }
+        0u8._serialize_chained(dest)?;
+        Ok(size)
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+        i += ::bebop::LEN_SIZE;
+
+        #[cfg(not(feature = "unchecked"))]
+        if len == 0 {
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        if raw.len() < len {
+            return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+        }
+
+        let mut _clicks = None;
+        let mut _clear = None;
+        let mut _leaderboard = None;
+        let mut _players = None;
+        let mut _delta_ids = None;
+        let mut _delta_values = None;
+        let mut _joined = None;
+        let mut _left = None;
+        let mut _throttled = None;
+        let mut _activate_powerup = None;
+        let mut _powerup = None;
+        let mut _error = None;
+        let mut _round = None;
+
+        #[cfg(not(feature = "unchecked"))]
+        let mut last = 0;
+
+        while i < len {
+            let di = raw[i];
+
+            #[cfg(not(feature = "unchecked"))]
+            if di != 0 {
+                if di < last {
+                    return Err(::bebop::DeserializeError::CorruptFrame);
+                }
+                last = di;
+            }
+
+            i += 1;
+            match di {
+                0 => {
+                    break;
+                }
+                2 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _clicks.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _clicks = Some(value)
+                }
+                3 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _clear.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _clear = Some(value)
+                }
+                4 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _leaderboard.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _leaderboard = Some(value)
+                }
+                5 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _players.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _players = Some(value)
+                }
+                6 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _delta_ids.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _delta_ids = Some(value)
+                }
+                7 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _delta_values.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _delta_values = Some(value)
+                }
+                8 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _joined.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _joined = Some(value)
+                }
+                9 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _left.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _left = Some(value)
+                }
+                10 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _throttled.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _throttled = Some(value)
+                }
+                11 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _activate_powerup.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _activate_powerup = Some(value)
+                }
+                12 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _powerup.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _powerup = Some(value)
+                }
+                13 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _error.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _error = Some(value)
+                }
+                14 => {
+                    #[cfg(not(feature = "unchecked"))]
+                    if _round.is_some() {
+                        return Err(::bebop::DeserializeError::DuplicateMessageField);
+                    }
+                    let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                    i += read;
+                    _round = Some(value)
+                }
+                _ => {
+                    i = len;
+                    break;
+                }
+            }
+        }
+
+        if i != len {
+            debug_assert!(i > len);
+            return Err(::bebop::DeserializeError::CorruptFrame);
+        }
+
+        Ok((
+            i,
+            Self {
+                clicks: _clicks,
+                clear: _clear,
+                leaderboard: _leaderboard,
+                players: _players,
+                delta_ids: _delta_ids,
+                delta_values: _delta_values,
+                joined: _joined,
+                left: _left,
+                throttled: _throttled,
+                activate_powerup: _activate_powerup,
+                powerup: _powerup,
+                error: _error,
+                round: _round,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for GameMessage<'raw> {}
+
+#[cfg(feature = "bebop-owned-all")]
+pub mod owned {
+    #![allow(warnings)]
+
+    use bebop::FixedSized as _;
+    use core::convert::TryInto as _;
+    use std::io::Write as _;
+
+    pub use super::Role;
+
+    pub use super::ErrorCode;
+
+    #[derive(Clone, Debug, PartialEq, Default)]
+    pub struct ErrorMessage {
+        /// Field 1
+        pub code: ::core::option::Option<ErrorCode>,
+        /// Field 2
+        pub message: ::core::option::Option<String>,
+        /// Field 3
+        pub retry: ::core::option::Option<bool>,
+        /// Field 4
+        pub id: ::core::option::Option<String>,
+    }
+
+    impl<'raw> ::core::convert::From<super::ErrorMessage<'raw>> for ErrorMessage {
+        fn from(value: super::ErrorMessage) -> Self {
+            Self {
+                code: value.code,
+                message: value.message.map(|value| value.into()),
+                retry: value.retry,
+                id: value.id.map(|value| value.into()),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for ErrorMessage {
+        const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            ::bebop::LEN_SIZE
+                + 1
+                + self
+                    .code
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .message
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .retry
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .id
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            let size = zelf.serialized_size();
+            ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+            if let Some(ref v) = zelf.code {
+                1u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.message {
+                2u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.retry {
+                3u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.id {
+                4u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            0u8._serialize_chained(dest)?;
+            Ok(size)
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+            i += ::bebop::LEN_SIZE;
+
+            #[cfg(not(feature = "unchecked"))]
+            if len == 0 {
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            if raw.len() < len {
+                return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+            }
+
+            let mut _code = None;
+            let mut _message = None;
+            let mut _retry = None;
+            let mut _id = None;
+
+            #[cfg(not(feature = "unchecked"))]
+            let mut last = 0;
+
+            while i < len {
+                let di = raw[i];
+
+                #[cfg(not(feature = "unchecked"))]
+                if di != 0 {
+                    if di < last {
+                        return Err(::bebop::DeserializeError::CorruptFrame);
+                    }
+                    last = di;
+                }
+
+                i += 1;
+                match di {
+                    0 => {
+                        break;
+                    }
+                    1 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _code.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _code = Some(value)
+                    }
+                    2 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _message.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _message = Some(value)
+                    }
+                    3 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _retry.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _retry = Some(value)
+                    }
+                    4 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _id.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _id = Some(value)
+                    }
+                    _ => {
+                        i = len;
+                        break;
+                    }
+                }
+            }
+
+            if i != len {
+                debug_assert!(i > len);
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            Ok((
+                i,
+                Self {
+                    code: _code,
+                    message: _message,
+                    retry: _retry,
+                    id: _id,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for ErrorMessage {}
+
+    #[derive(Clone, Debug, PartialEq, Default)]
+    pub struct Hello {
+        /// Field 1
+        pub protocol_version: ::core::option::Option<u32>,
+        /// Field 2
+        pub client_name: ::core::option::Option<String>,
+        /// Field 3
+        pub capabilities: ::core::option::Option<::std::vec::Vec<String>>,
+        /// Field 4
+        pub role: ::core::option::Option<Role>,
+        /// Field 5
+        pub host_token: ::core::option::Option<String>,
+        /// Field 6
+        pub resume_token: ::core::option::Option<String>,
+    }
+
+    impl<'raw> ::core::convert::From<super::Hello<'raw>> for Hello {
+        fn from(value: super::Hello) -> Self {
+            Self {
+                protocol_version: value.protocol_version,
+                client_name: value.client_name.map(|value| value.into()),
+                capabilities: value
+                    .capabilities
+                    .map(|value| value.into_iter().map(|value| value.into()).collect()),
+                role: value.role,
+                host_token: value.host_token.map(|value| value.into()),
+                resume_token: value.resume_token.map(|value| value.into()),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for Hello {
+        const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            ::bebop::LEN_SIZE
+                + 1
+                + self
+                    .protocol_version
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .client_name
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .capabilities
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .role
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .host_token
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .resume_token
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            let size = zelf.serialized_size();
+            ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+            if let Some(ref v) = zelf.protocol_version {
+                1u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.client_name {
+                2u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.capabilities {
+                3u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.role {
+                4u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.host_token {
+                5u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.resume_token {
+                6u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            0u8._serialize_chained(dest)?;
+            Ok(size)
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+            i += ::bebop::LEN_SIZE;
+
+            #[cfg(not(feature = "unchecked"))]
+            if len == 0 {
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            if raw.len() < len {
+                return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+            }
+
+            let mut _protocol_version = None;
+            let mut _client_name = None;
+            let mut _capabilities = None;
+            let mut _role = None;
+            let mut _host_token = None;
+            let mut _resume_token = None;
+
+            #[cfg(not(feature = "unchecked"))]
+            let mut last = 0;
+
+            while i < len {
+                let di = raw[i];
+
+                #[cfg(not(feature = "unchecked"))]
+                if di != 0 {
+                    if di < last {
+                        return Err(::bebop::DeserializeError::CorruptFrame);
+                    }
+                    last = di;
+                }
+
+                i += 1;
+                match di {
+                    0 => {
+                        break;
+                    }
+                    1 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _protocol_version.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _protocol_version = Some(value)
+                    }
+                    2 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _client_name.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _client_name = Some(value)
+                    }
+                    3 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _capabilities.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _capabilities = Some(value)
+                    }
+                    4 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _role.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _role = Some(value)
+                    }
+                    5 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _host_token.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _host_token = Some(value)
+                    }
+                    6 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _resume_token.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _resume_token = Some(value)
+                    }
+                    _ => {
+                        i = len;
+                        break;
+                    }
+                }
+            }
+
+            if i != len {
+                debug_assert!(i > len);
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            Ok((
+                i,
+                Self {
+                    protocol_version: _protocol_version,
+                    client_name: _client_name,
+                    capabilities: _capabilities,
+                    role: _role,
+                    host_token: _host_token,
+                    resume_token: _resume_token,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for Hello {}
+
+    pub use super::Rules;
+
+    #[derive(Clone, Debug, PartialEq, Default)]
+    pub struct Welcome {
+        /// Field 1
+        pub server_version: ::core::option::Option<String>,
+        /// Field 2
+        pub room: ::core::option::Option<String>,
+        /// Field 3
+        pub player_id: ::core::option::Option<u32>,
+        /// Field 4
+        pub role: ::core::option::Option<Role>,
+        /// Field 5
+        pub resume_token: ::core::option::Option<String>,
+        /// Field 6
+        pub name_error: ::core::option::Option<String>,
+        /// Field 7
+        pub error: ::core::option::Option<ErrorMessage>,
+        /// Field 8
+        pub rules: ::core::option::Option<Rules>,
+        /// Field 9
+        pub capabilities: ::core::option::Option<::std::vec::Vec<String>>,
+    }
+
+    impl<'raw> ::core::convert::From<super::Welcome<'raw>> for Welcome {
+        fn from(value: super::Welcome) -> Self {
+            Self {
+                server_version: value.server_version.map(|value| value.into()),
+                room: value.room.map(|value| value.into()),
+                player_id: value.player_id,
+                role: value.role,
+                resume_token: value.resume_token.map(|value| value.into()),
+                name_error: value.name_error.map(|value| value.into()),
+                error: value.error.map(|value| value.into()),
+                rules: value.rules,
+                capabilities: value
+                    .capabilities
+                    .map(|value| value.into_iter().map(|value| value.into()).collect()),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for Welcome {
+        const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            ::bebop::LEN_SIZE
+                + 1
+                + self
+                    .server_version
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .room
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .player_id
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .role
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .resume_token
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .name_error
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .error
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .rules
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .capabilities
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            let size = zelf.serialized_size();
+            ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+            if let Some(ref v) = zelf.server_version {
+                1u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.room {
+                2u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.player_id {
+                3u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.role {
+                4u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.resume_token {
+                5u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.name_error {
+                6u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.error {
+                7u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.rules {
+                8u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.capabilities {
+                9u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            0u8._serialize_chained(dest)?;
+            Ok(size)
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+            i += ::bebop::LEN_SIZE;
+
+            #[cfg(not(feature = "unchecked"))]
+            if len == 0 {
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            if raw.len() < len {
+                return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+            }
+
+            let mut _server_version = None;
+            let mut _room = None;
+            let mut _player_id = None;
+            let mut _role = None;
+            let mut _resume_token = None;
+            let mut _name_error = None;
+            let mut _error = None;
+            let mut _rules = None;
+            let mut _capabilities = None;
+
+            #[cfg(not(feature = "unchecked"))]
+            let mut last = 0;
+
+            while i < len {
+                let di = raw[i];
+
+                #[cfg(not(feature = "unchecked"))]
+                if di != 0 {
+                    if di < last {
+                        return Err(::bebop::DeserializeError::CorruptFrame);
+                    }
+                    last = di;
+                }
+
+                i += 1;
+                match di {
+                    0 => {
+                        break;
+                    }
+                    1 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _server_version.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _server_version = Some(value)
+                    }
+                    2 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _room.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _room = Some(value)
+                    }
+                    3 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _player_id.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _player_id = Some(value)
+                    }
+                    4 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _role.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _role = Some(value)
+                    }
+                    5 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _resume_token.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _resume_token = Some(value)
+                    }
+                    6 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _name_error.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _name_error = Some(value)
+                    }
+                    7 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _error.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _error = Some(value)
+                    }
+                    8 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _rules.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _rules = Some(value)
+                    }
+                    9 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _capabilities.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _capabilities = Some(value)
+                    }
+                    _ => {
+                        i = len;
+                        break;
+                    }
+                }
+            }
+
+            if i != len {
+                debug_assert!(i > len);
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            Ok((
+                i,
+                Self {
+                    server_version: _server_version,
+                    room: _room,
+                    player_id: _player_id,
+                    role: _role,
+                    resume_token: _resume_token,
+                    name_error: _name_error,
+                    error: _error,
+                    rules: _rules,
+                    capabilities: _capabilities,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for Welcome {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct RankedKeyValue {
+        pub rank: u32,
+        pub key: String,
+        pub value: i64,
+    }
+
+    impl<'raw> ::core::convert::From<super::RankedKeyValue<'raw>> for RankedKeyValue {
+        fn from(value: super::RankedKeyValue) -> Self {
+            Self {
+                rank: value.rank,
+                key: value.key.into(),
+                value: value.value,
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for RankedKeyValue {
+        const MIN_SERIALIZED_SIZE: usize =
+            <u32>::MIN_SERIALIZED_SIZE + <String>::MIN_SERIALIZED_SIZE + <i64>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.rank.serialized_size() + self.key.serialized_size() + self.value.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.rank._serialize_chained(dest)? +
+                zelf.key._serialize_chained(dest)? +
+                zelf.value._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    rank: v0,
+                    key: v1,
+                    value: v2,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for RankedKeyValue {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Player {
+        pub id: u32,
+        pub name: String,
+        pub value: i64,
+    }
+
+    impl<'raw> ::core::convert::From<super::Player<'raw>> for Player {
+        fn from(value: super::Player) -> Self {
+            Self {
+                id: value.id,
+                name: value.name.into(),
+                value: value.value,
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for Player {
+        const MIN_SERIALIZED_SIZE: usize =
+            <u32>::MIN_SERIALIZED_SIZE + <String>::MIN_SERIALIZED_SIZE + <i64>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.id.serialized_size() + self.name.serialized_size() + self.value.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.id._serialize_chained(dest)? +
+                zelf.name._serialize_chained(dest)? +
+                zelf.value._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    id: v0,
+                    name: v1,
+                    value: v2,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for Player {}
+
+    pub use super::Click;
+
+    pub use super::PowerupKind;
+
+    pub use super::PowerupState;
+
+    #[derive(Clone, Debug, PartialEq, Default)]
+    pub struct PlayerJoined {
+        /// Field 1
+        pub id: ::core::option::Option<u32>,
+        /// Field 2
+        pub name: ::core::option::Option<String>,
+        /// Field 3
+        pub value: ::core::option::Option<i64>,
+    }
+
+    impl<'raw> ::core::convert::From<super::PlayerJoined<'raw>> for PlayerJoined {
+        fn from(value: super::PlayerJoined) -> Self {
+            Self {
+                id: value.id,
+                name: value.name.map(|value| value.into()),
+                value: value.value,
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for PlayerJoined {
+        const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            ::bebop::LEN_SIZE
+                + 1
+                + self
+                    .id
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .name
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .value
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            let size = zelf.serialized_size();
+            ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+            if let Some(ref v) = zelf.id {
+                1u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.name {
+                2u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.value {
+                3u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            0u8._serialize_chained(dest)?;
+            Ok(size)
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+            i += ::bebop::LEN_SIZE;
+
+            #[cfg(not(feature = "unchecked"))]
+            if len == 0 {
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            if raw.len() < len {
+                return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+            }
+
+            let mut _id = None;
+            let mut _name = None;
+            let mut _value = None;
+
+            #[cfg(not(feature = "unchecked"))]
+            let mut last = 0;
+
+            while i < len {
+                let di = raw[i];
+
+                #[cfg(not(feature = "unchecked"))]
+                if di != 0 {
+                    if di < last {
+                        return Err(::bebop::DeserializeError::CorruptFrame);
+                    }
+                    last = di;
+                }
+
+                i += 1;
+                match di {
+                    0 => {
+                        break;
+                    }
+                    1 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _id.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _id = Some(value)
+                    }
+                    2 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _name.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _name = Some(value)
+                    }
+                    3 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _value.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _value = Some(value)
+                    }
+                    _ => {
+                        i = len;
+                        break;
+                    }
+                }
+            }
+
+            if i != len {
+                debug_assert!(i > len);
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            Ok((
+                i,
+                Self {
+                    id: _id,
+                    name: _name,
+                    value: _value,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for PlayerJoined {}
+
+    pub use super::PlayerLeft;
+
+    pub use super::RoundPhase;
+
+    #[derive(Clone, Debug, PartialEq, Default)]
+    pub struct RoundState {
+        /// Field 1
+        pub phase: ::core::option::Option<RoundPhase>,
+        /// Field 2
+        pub number: ::core::option::Option<u32>,
+        /// Field 3
+        pub remaining_ms: ::core::option::Option<u32>,
+        /// Field 4
+        pub duration_ms: ::core::option::Option<u32>,
+        /// Field 5
+        pub min_players: ::core::option::Option<u32>,
+        /// Field 6
+        pub podium: ::core::option::Option<::std::vec::Vec<RankedKeyValue>>,
+    }
+
+    impl<'raw> ::core::convert::From<super::RoundState<'raw>> for RoundState {
+        fn from(value: super::RoundState) -> Self {
+            Self {
+                phase: value.phase,
+                number: value.number,
+                remaining_ms: value.remaining_ms,
+                duration_ms: value.duration_ms,
+                min_players: value.min_players,
+                podium: value
+                    .podium
+                    .map(|value| value.into_iter().map(|value| value.into()).collect()),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for RoundState {
+        const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            ::bebop::LEN_SIZE
+                + 1
+                + self
+                    .phase
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .number
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .remaining_ms
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .duration_ms
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .min_players
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .podium
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            let size = zelf.serialized_size();
+            ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+            if let Some(ref v) = zelf.phase {
+                1u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.number {
+                2u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.remaining_ms {
+                3u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.duration_ms {
+                4u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.min_players {
+                5u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.podium {
+                6u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            0u8._serialize_chained(dest)?;
+            Ok(size)
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+            i += ::bebop::LEN_SIZE;
+
+            #[cfg(not(feature = "unchecked"))]
+            if len == 0 {
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            if raw.len() < len {
+                return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+            }
+
+            let mut _phase = None;
+            let mut _number = None;
+            let mut _remaining_ms = None;
+            let mut _duration_ms = None;
+            let mut _min_players = None;
+            let mut _podium = None;
+
+            #[cfg(not(feature = "unchecked"))]
+            let mut last = 0;
+
+            while i < len {
+                let di = raw[i];
+
+                #[cfg(not(feature = "unchecked"))]
+                if di != 0 {
+                    if di < last {
+                        return Err(::bebop::DeserializeError::CorruptFrame);
+                    }
+                    last = di;
+                }
+
+                i += 1;
+                match di {
+                    0 => {
+                        break;
+                    }
+                    1 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _phase.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _phase = Some(value)
+                    }
+                    2 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _number.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _number = Some(value)
+                    }
+                    3 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _remaining_ms.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _remaining_ms = Some(value)
+                    }
+                    4 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _duration_ms.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _duration_ms = Some(value)
+                    }
+                    5 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _min_players.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _min_players = Some(value)
+                    }
+                    6 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _podium.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _podium = Some(value)
+                    }
+                    _ => {
+                        i = len;
+                        break;
+                    }
+                }
+            }
+
+            if i != len {
+                debug_assert!(i > len);
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            Ok((
+                i,
+                Self {
+                    phase: _phase,
+                    number: _number,
+                    remaining_ms: _remaining_ms,
+                    duration_ms: _duration_ms,
+                    min_players: _min_players,
+                    podium: _podium,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for RoundState {}
+
+    #[derive(Clone, Debug, PartialEq, Default)]
+    pub struct GameMessage {
+        /// Field 2
+        pub clicks: ::core::option::Option<::std::vec::Vec<Click>>,
+        /// Field 3
+        pub clear: ::core::option::Option<bool>,
+        /// Field 4
+        pub leaderboard: ::core::option::Option<::std::vec::Vec<RankedKeyValue>>,
+        /// Field 5
+        pub players: ::core::option::Option<::std::vec::Vec<Player>>,
+        /// Field 6
+        pub delta_ids: ::core::option::Option<::std::vec::Vec<u32>>,
+        /// Field 7
+        pub delta_values: ::core::option::Option<::std::vec::Vec<i64>>,
+        /// Field 8
+        pub joined: ::core::option::Option<::std::vec::Vec<PlayerJoined>>,
+        /// Field 9
+        pub left: ::core::option::Option<::std::vec::Vec<PlayerLeft>>,
+        /// Field 10
+        pub throttled: ::core::option::Option<bool>,
+        /// Field 11
+        pub activate_powerup: ::core::option::Option<bool>,
+        /// Field 12
+        pub powerup: ::core::option::Option<PowerupState>,
+        /// Field 13
+        pub error: ::core::option::Option<ErrorMessage>,
+        /// Field 14
+        pub round: ::core::option::Option<RoundState>,
+    }
+
+    impl<'raw> ::core::convert::From<super::GameMessage<'raw>> for GameMessage {
+        fn from(value: super::GameMessage) -> Self {
+            Self {
+                clicks: value.clicks,
+                clear: value.clear,
+                leaderboard: value
+                    .leaderboard
+                    .map(|value| value.into_iter().map(|value| value.into()).collect()),
+                players: value
+                    .players
+                    .map(|value| value.into_iter().map(|value| value.into()).collect()),
+                delta_ids: value.delta_ids.map(|value| value.iter().collect()),
+                delta_values: value.delta_values.map(|value| value.iter().collect()),
+                joined: value
+                    .joined
+                    .map(|value| value.into_iter().map(|value| value.into()).collect()),
+                left: value.left,
+                throttled: value.throttled,
+                activate_powerup: value.activate_powerup,
+                powerup: value.powerup,
+                error: value.error.map(|value| value.into()),
+                round: value.round.map(|value| value.into()),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for GameMessage {
+        const MIN_SERIALIZED_SIZE: usize = ::bebop::LEN_SIZE + 1;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            ::bebop::LEN_SIZE
+                + 1
+                + self
+                    .clicks
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .clear
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .leaderboard
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .players
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .delta_ids
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .delta_values
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .joined
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .left
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .throttled
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .activate_powerup
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .powerup
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .error
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+                + self
+                    .round
+                    .as_ref()
+                    .map(|v| v.serialized_size() + 1)
+                    .unwrap_or(0)
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            let size = zelf.serialized_size();
+            ::bebop::write_len(dest, size - ::bebop::LEN_SIZE)?;
+            if let Some(ref v) = zelf.clicks {
+                2u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.clear {
+                3u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.leaderboard {
+                4u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.players {
+                5u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.delta_ids {
+                6u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.delta_values {
+                7u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.joined {
+                8u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.left {
+                9u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.throttled {
+                10u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.activate_powerup {
+                11u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.powerup {
+                12u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.error {
+                13u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            if let Some(ref v) = zelf.round {
+                14u8._serialize_chained(dest)?;
+                v._serialize_chained(dest)?;
+            }
+            0u8._serialize_chained(dest)?;
+            Ok(size)
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            let len = ::bebop::read_len(&raw[i..])? + ::bebop::LEN_SIZE;
+            i += ::bebop::LEN_SIZE;
+
+            #[cfg(not(feature = "unchecked"))]
+            if len == 0 {
+                return Err(::bebop::DeserializeError::CorruptFrame);
+            }
+
+            if raw.len() < len {
+                return Err(::bebop::DeserializeError::MoreDataExpected(len - raw.len()));
+            }
+
+            let mut _clicks = None;
             let mut _clear = None;
+            let mut _leaderboard = None;
+            let mut _players = None;
+            let mut _delta_ids = None;
+            let mut _delta_values = None;
+            let mut _joined = None;
+            let mut _left = None;
+            let mut _throttled = None;
+            let mut _activate_powerup = None;
+            let mut _powerup = None;
+            let mut _error = None;
+            let mut _round = None;
 
             #[cfg(not(feature = "unchecked"))]
             let mut last = 0;
@@ -363,15 +3735,6 @@ pub mod owned {
                     0 => {
                         break;
                     }
-                    1 => {
-                        #[cfg(not(feature = "unchecked"))]
-                        if _updates.is_some() {
-                            return Err(::bebop::DeserializeError::DuplicateMessageField);
-                        }
-                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
-                        i += read;
-                        _updates = Some(value)
-                    }
                     2 => {
                         #[cfg(not(feature = "unchecked"))]
                         if _clicks.is_some() {
@@ -390,6 +3753,105 @@ pub mod owned {
                         i += read;
                         _clear = Some(value)
                     }
+                    4 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _leaderboard.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _leaderboard = Some(value)
+                    }
+                    5 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _players.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _players = Some(value)
+                    }
+                    6 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _delta_ids.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _delta_ids = Some(value)
+                    }
+                    7 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _delta_values.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _delta_values = Some(value)
+                    }
+                    8 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _joined.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _joined = Some(value)
+                    }
+                    9 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _left.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _left = Some(value)
+                    }
+                    10 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _throttled.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _throttled = Some(value)
+                    }
+                    11 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _activate_powerup.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _activate_powerup = Some(value)
+                    }
+                    12 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _powerup.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _powerup = Some(value)
+                    }
+                    13 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _error.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _error = Some(value)
+                    }
+                    14 => {
+                        #[cfg(not(feature = "unchecked"))]
+                        if _round.is_some() {
+                            return Err(::bebop::DeserializeError::DuplicateMessageField);
+                        }
+                        let (read, value) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+                        i += read;
+                        _round = Some(value)
+                    }
                     _ => {
                         i = len;
                         break;
@@ -405,9 +3867,19 @@ pub mod owned {
             Ok((
                 i,
                 Self {
-                    updates: _updates,
                     clicks: _clicks,
                     clear: _clear,
+                    leaderboard: _leaderboard,
+                    players: _players,
+                    delta_ids: _delta_ids,
+                    delta_values: _delta_values,
+                    joined: _joined,
+                    left: _left,
+                    throttled: _throttled,
+                    activate_powerup: _activate_powerup,
+                    powerup: _powerup,
+                    error: _error,
+                    round: _round,
                 },
             ))
         }
@@ -0,0 +1,162 @@
+//! Prometheus metrics served on `/metrics`
+//!
+//! Every replica keeps its own counts, so sum them up across
+//! replicas to see a whole event.
+use std::{sync::LazyLock, time::Instant};
+
+use axum::{
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use prometheus_client::{
+    encoding::{EncodeLabelSet, text::encode},
+    metrics::{
+        counter::Counter,
+        family::Family,
+        gauge::Gauge,
+        histogram::{Histogram, exponential_buckets},
+    },
+    registry::Registry,
+};
+use tracing::Instrument;
+
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+type HistogramFamily<L> = Family<L, Histogram, fn() -> Histogram>;
+
+pub struct Metrics {
+    registry: Registry,
+    /// Open WebSockets, whether they made it through the handshake or not
+    pub sockets: Gauge,
+    pub joins: Counter,
+    pub resumes: Counter,
+    pub leaves: Family<LeaveLabels, Counter>,
+    pub clicks_applied: Counter,
+    pub clicks_dropped: Counter,
+    /// Messages that got their client kicked
+    pub rejected_messages: Counter,
+    pub redis_latency: HistogramFamily<CommandLabels>,
+    pub tick_duration: Histogram,
+    /// Frames still waiting for a socket each time it's sent one
+    pub send_backlog: Histogram,
+    /// Times a socket fell so far behind it was sent a snapshot instead
+    pub lagged: Counter,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct LeaveLabels {
+    /// `left` when they closed the page or were kicked,
+    /// `expired` when their grace period ran out
+    pub reason: &'static str,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct CommandLabels {
+    pub command: &'static str,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let mut metrics = Self {
+            registry: Registry::with_prefix("redis_game"),
+            sockets: Gauge::default(),
+            joins: Counter::default(),
+            resumes: Counter::default(),
+            leaves: Family::default(),
+            clicks_applied: Counter::default(),
+            clicks_dropped: Counter::default(),
+            rejected_messages: Counter::default(),
+            redis_latency: Family::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(0.0005, 2.0, 12))
+            }),
+            tick_duration: Histogram::new(exponential_buckets(0.001, 2.0, 10)),
+            send_backlog: Histogram::new(exponential_buckets(1.0, 2.0, 7)),
+            lagged: Counter::default(),
+        };
+        metrics.registry.register(
+            "sockets",
+            "Open WebSocket connections",
+            metrics.sockets.clone(),
+        );
+        metrics
+            .registry
+            .register("joins", "Players who joined a room", metrics.joins.clone());
+        metrics.registry.register(
+            "resumes",
+            "Players who picked their game back up after their connection dropped",
+            metrics.resumes.clone(),
+        );
+        metrics.registry.register(
+            "leaves",
+            "Players who were removed from a room",
+            metrics.leaves.clone(),
+        );
+        metrics.registry.register(
+            "clicks_applied",
+            "Clicks that changed a player's score",
+            metrics.clicks_applied.clone(),
+        );
+        metrics.registry.register(
+            "clicks_dropped",
+            "Clicks dropped for coming in too fast, not fitting the active powerup or landing outside a round",
+            metrics.clicks_dropped.clone(),
+        );
+        metrics.registry.register(
+            "rejected_messages",
+            "Client messages that got their client kicked",
+            metrics.rejected_messages.clone(),
+        );
+        metrics.registry.register(
+            "redis_latency_seconds",
+            "How long Redis commands took",
+            metrics.redis_latency.clone(),
+        );
+        metrics.registry.register(
+            "tick_duration_seconds",
+            "How long it took to send every room's updates",
+            metrics.tick_duration.clone(),
+        );
+        metrics.registry.register(
+            "send_backlog_frames",
+            "Frames still waiting for a socket when it's sent one",
+            metrics.send_backlog.clone(),
+        );
+        metrics.registry.register(
+            "lagged",
+            "Times a socket fell too far behind and was sent a snapshot",
+            metrics.lagged.clone(),
+        );
+        metrics
+    }
+}
+
+/// Runs a Redis command in its own span, recording how long it took under `command`
+pub async fn timed<T>(command: &'static str, future: impl Future<Output = T>) -> T {
+    let start = Instant::now();
+    let result = future
+        .instrument(tracing::info_span!("redis", command))
+        .await;
+    METRICS
+        .redis_latency
+        .get_or_create(&CommandLabels { command })
+        .observe(start.elapsed().as_secs_f64());
+    result
+}
+
+/// Every metric in the OpenMetrics text format
+pub async fn serve() -> impl IntoResponse {
+    let mut body = String::new();
+    match encode(&mut body, &METRICS.registry) {
+        Ok(()) => (StatusCode::OK, [(header::CONTENT_TYPE, CONTENT_TYPE)], body),
+        Err(e) => {
+            tracing::error!(?e, "Failed to encode metrics");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                [(header::CONTENT_TYPE, "text/plain")],
+                String::new(),
+            )
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Rules for the names players pick.
+//!
+//! Names end up in Redis keys, pub/sub payloads and on everyone's screen,
+//! so they are normalised and checked before anyone joins with one.
+use std::{collections::HashSet, fmt::Display, path::Path};
+
+use clap::ValueEnum;
+use color_eyre::eyre::{self, Context};
+use serde::{Deserialize, Serialize};
+use unicode_general_category::{GeneralCategory, get_general_category};
+use unicode_normalization::UnicodeNormalization;
+
+/// Characters that are never allowed since they separate the parts of Redis keys
+const RESERVED: [char; 1] = [':'];
+/// Longest a character gets in UTF-8, names longer than this many bytes per
+/// allowed character are turned down before doing any work on them
+const MAX_CHAR_BYTES: usize = 4;
+
+/// Kinds of characters a name may be made of
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameChars {
+    /// Letters and the marks that combine with them, in any script
+    Letters,
+    /// Digits and other numbers
+    Digits,
+    /// Spaces between words
+    Spaces,
+    /// Punctuation like `-`, `_`, `.` or `!`
+    Punctuation,
+    /// Symbols like `+`, `$` or emoji
+    Symbols,
+}
+
+/// What a name has to look like to join with it
+pub struct NameRules {
+    pub min_len: usize,
+    pub max_len: usize,
+    pub chars: Vec<NameChars>,
+    /// Lowercase words no name may contain
+    pub blocklist: HashSet<String>,
+}
+
+/// Why a name was turned down, shown to the player so they can pick another one
+#[derive(Debug)]
+pub enum NameError {
+    TooShort(usize),
+    TooLong(usize),
+    Character(char),
+    Blocked,
+}
+
+impl NameChars {
+    fn allows(self, c: char) -> bool {
+        use GeneralCategory::*;
+        let category = get_general_category(c);
+        match self {
+            Self::Letters => matches!(
+                category,
+                UppercaseLetter
+                    | LowercaseLetter
+                    | TitlecaseLetter
+                    | ModifierLetter
+                    | OtherLetter
+                    | NonspacingMark
+                    | SpacingMark
+                    | EnclosingMark
+            ),
+            Self::Digits => matches!(category, DecimalNumber | LetterNumber | OtherNumber),
+            Self::Spaces => c == ' ',
+            Self::Punctuation => matches!(
+                category,
+                ConnectorPunctuation
+                    | DashPunctuation
+                    | OpenPunctuation
+                    | ClosePunctuation
+                    | InitialPunctuation
+                    | FinalPunctuation
+                    | OtherPunctuation
+            ),
+            Self::Symbols => matches!(
+                category,
+                MathSymbol | CurrencySymbol | ModifierSymbol | OtherSymbol
+            ),
+        }
+    }
+}
+
+impl NameRules {
+    /// Reads a blocklist file with one word per line,
+    /// empty lines and lines starting with `#` are skipped
+    pub fn load_blocklist(path: &Path) -> eyre::Result<HashSet<String>> {
+        let list = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read name blocklist `{}`", path.display()))?;
+        Ok(list
+            .lines()
+            .map(str::trim)
+            .filter(|word| !word.is_empty() && !word.starts_with('#'))
+            .map(|word| word.nfkc().flat_map(char::to_lowercase).collect())
+            .collect())
+    }
+
+    /// Normalises `name` and checks it against the rules, returning
+    /// the name the player joins with
+    pub fn check(&self, name: &str) -> Result<String, NameError> {
+        if name.len() > self.max_len * MAX_CHAR_BYTES {
+            return Err(NameError::TooLong(self.max_len));
+        }
+        let name = normalise(name);
+
+        let len = name.chars().count();
+        if len < self.min_len {
+            return Err(NameError::TooShort(self.min_len));
+        }
+        if len > self.max_len {
+            return Err(NameError::TooLong(self.max_len));
+        }
+        if let Some(c) = name
+            .chars()
+            .find(|&c| RESERVED.contains(&c) || !self.chars.iter().any(|chars| chars.allows(c)))
+        {
+            return Err(NameError::Character(c));
+        }
+
+        let lowercase = name.to_lowercase();
+        if lowercase
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| self.blocklist.contains(word))
+        {
+            return Err(NameError::Blocked);
+        }
+
+        Ok(name)
+    }
+}
+
+/// Folds look-alikes like full width letters together, and makes the same
+/// name typed on different keyboards the same string
+pub fn normalise(name: &str) -> String {
+    name.nfkc()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl Display for NameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooShort(min) => write!(f, "Names need at least {} characters", min),
+            Self::TooLong(max) => write!(f, "Names can't be longer than {} characters", max),
+            Self::Character(c) if c.is_control() || c.is_whitespace() => {
+                write!(f, "Names can't contain {:?}", c)
+            }
+            Self::Character(c) => write!(f, "Names can't contain `{}`", c),
+            Self::Blocked => f.write_str("That name isn't allowed, pick another one"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> NameRules {
+        NameRules {
+            min_len: 2,
+            max_len: 5,
+            chars: vec![
+                NameChars::Letters,
+                NameChars::Digits,
+                NameChars::Spaces,
+                NameChars::Punctuation,
+            ],
+            blocklist: HashSet::from(["bad".to_owned()]),
+        }
+    }
+
+    #[test]
+    fn normalises_names() {
+        let rules = rules();
+        assert_eq!(rules.check("ｆｕｌｌ").unwrap(), "full");
+        assert_eq!(rules.check("  a \t b ").unwrap(), "a b");
+        // An `e` with a combining accent is the same name as a precomposed `é`
+        assert_eq!(rules.check("cafe\u{301}").unwrap(), "café");
+    }
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        let rules = rules();
+        assert_eq!(rules.check("ééééé").unwrap(), "ééééé");
+        assert!(matches!(rules.check("abcdef"), Err(NameError::TooLong(5))));
+        assert!(matches!(rules.check("é"), Err(NameError::TooShort(2))));
+        assert!(matches!(rules.check("   "), Err(NameError::TooShort(2))));
+        assert!(matches!(
+            rules.check(&"a".repeat(100)),
+            Err(NameError::TooLong(5))
+        ));
+    }
+
+    #[test]
+    fn checks_categories() {
+        let mut rules = rules();
+        assert!(matches!(rules.check("a:b"), Err(NameError::Character(':'))));
+        assert!(matches!(
+            rules.check("a🦀"),
+            Err(NameError::Character('🦀'))
+        ));
+        assert!(matches!(
+            rules.check("a\u{7}"),
+            Err(NameError::Character('\u{7}'))
+        ));
+        assert_eq!(rules.check("a-1!").unwrap(), "a-1!");
+
+        rules.chars = vec![NameChars::Letters, NameChars::Symbols];
+        assert_eq!(rules.check("a🦀").unwrap(), "a🦀");
+        assert!(matches!(rules.check("a1"), Err(NameError::Character('1'))));
+        assert!(matches!(rules.check("a b"), Err(NameError::Character(' '))));
+        // Reserved even when symbols and punctuation are allowed
+        rules.chars.push(NameChars::Punctuation);
+        assert!(matches!(rules.check("a:b"), Err(NameError::Character(':'))));
+    }
+
+    #[test]
+    fn blocks_whole_words() {
+        let rules = rules();
+        assert!(matches!(rules.check("bad"), Err(NameError::Blocked)));
+        assert!(matches!(rules.check("a BAD"), Err(NameError::Blocked)));
+        assert!(matches!(rules.check("ｂａｄ!"), Err(NameError::Blocked)));
+        assert_eq!(rules.check("badge").unwrap(), "badge");
+    }
+}
@@ -0,0 +1,179 @@
+//! Powerups let a player click several cells at once for a while.
+//!
+//! The server grants them and keeps their cooldown in Redis, so a click
+//! on many cells can be told apart from a forged one by checking it
+//! against the shape of the player's active powerup.
+use std::time::Duration;
+
+use color_eyre::eyre::{self, Context};
+use redis::{AsyncTypedCommands, aio::ConnectionManager};
+
+use crate::{messages::redis_game::PowerupKind, metrics, room::Room, rules::GameRules, scripts};
+
+const KINDS: u32 = 5;
+
+/// What a player got when they tried to activate a powerup, or where their
+/// powerup is at when they come back
+pub struct Grant {
+    /// Not set when the last powerup is still cooling down
+    pub kind: Option<PowerupKind>,
+    pub active_for: Duration,
+    pub ready_in: Duration,
+}
+
+/// Grants the player with `player_id` a powerup for as long as the rules
+/// say, or tells them how long until they can have one. With rounds on,
+/// they only get one while a round is being played.
+pub async fn activate(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    player_id: u32,
+    rules: &GameRules,
+) -> eyre::Result<Grant> {
+    let (kind, active_ms, ready_in_ms): (u32, u64, u64) = metrics::timed(
+        "activate_powerup",
+        scripts::ACTIVATE_POWERUP
+            .key(room.powerup_key(player_id))
+            .key(room.powerup_cooldown_key(player_id))
+            .key(room.round_key())
+            .arg(rules.powerup.as_millis() as u64)
+            .arg((rules.powerup + rules.powerup_cooldown).as_millis() as u64)
+            .arg(KINDS)
+            .arg(u8::from(rules.rounds()))
+            .invoke_async(connection),
+    )
+    .await
+    .wrap_err("Failed to activate powerup")?;
+
+    Ok(Grant {
+        kind: PowerupKind::try_from(kind).ok(),
+        active_for: Duration::from_millis(active_ms),
+        ready_in: Duration::from_millis(ready_in_ms),
+    })
+}
+
+/// Where the powerup of the player with `player_id` is at, so a player who
+/// resumed knows what they have active and how long until the next one
+pub async fn state(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    player_id: u32,
+) -> eyre::Result<Grant> {
+    let (kind, active_ms, ready_in_ms): (Option<u32>, i64, i64) = metrics::timed(
+        "read_powerup",
+        redis::pipe()
+            .get(room.powerup_key(player_id))
+            .pttl(room.powerup_key(player_id))
+            .pttl(room.powerup_cooldown_key(player_id))
+            .query_async(connection),
+    )
+    .await
+    .wrap_err("Failed to read the powerup state")?;
+
+    // PTTL is negative for keys that ran out
+    Ok(Grant {
+        kind: kind.and_then(|kind| PowerupKind::try_from(kind).ok()),
+        active_for: Duration::from_millis(active_ms.max(0) as u64),
+        ready_in: Duration::from_millis(ready_in_ms.max(0) as u64),
+    })
+}
+
+/// The powerup the player with `player_id` has active. It's read from Redis
+/// rather than remembered by the socket, so it survives a resume on any replica.
+pub async fn active(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    player_id: u32,
+) -> eyre::Result<Option<PowerupKind>> {
+    let kind = metrics::timed("read_powerup", connection.get(room.powerup_key(player_id)))
+        .await
+        .wrap_err("Failed to read the active powerup")?;
+    Ok(kind
+        .and_then(|kind| kind.parse::<u32>().ok())
+        .and_then(|kind| PowerupKind::try_from(kind).ok()))
+}
+
+/// Whether a single message's clicked cells could all have come from one
+/// click with `powerup` active, mirroring how the frontend highlights cells
+pub fn fits_shape(powerup: Option<PowerupKind>, cells: &[(usize, usize)]) -> bool {
+    let crosses = |column_parity: usize, row_parity: usize| {
+        cells
+            .iter()
+            .all(|&(x, y)| x % 2 == column_parity || y % 2 == row_parity)
+    };
+    match powerup {
+        None | Some(PowerupKind::Autoclick) => cells.len() <= 1,
+        Some(PowerupKind::Rows) => cells.iter().all(|&(_, y)| y % 2 == cells[0].1 % 2),
+        Some(PowerupKind::Columns) => cells.iter().all(|&(x, _)| x % 2 == cells[0].0 % 2),
+        // The cursor's row and column parity can be any of the four combinations
+        Some(PowerupKind::RowsColumns) => {
+            (0..2).any(|column_parity| (0..2).any(|row_parity| crosses(column_parity, row_parity)))
+        }
+        Some(PowerupKind::Random) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POWERUPS: [Option<PowerupKind>; 6] = [
+        None,
+        Some(PowerupKind::Autoclick),
+        Some(PowerupKind::Rows),
+        Some(PowerupKind::Columns),
+        Some(PowerupKind::RowsColumns),
+        Some(PowerupKind::Random),
+    ];
+
+    #[test]
+    fn no_cells_or_one_fit_every_shape() {
+        for powerup in POWERUPS {
+            assert!(fits_shape(powerup, &[]));
+            assert!(fits_shape(powerup, &[(3, 4)]));
+        }
+    }
+
+    #[test]
+    fn several_cells_need_a_powerup_that_hits_several() {
+        assert!(!fits_shape(None, &[(0, 0), (2, 0)]));
+        assert!(!fits_shape(Some(PowerupKind::Autoclick), &[(0, 0), (2, 0)]));
+    }
+
+    #[test]
+    fn fits_rows() {
+        let every_other_row = [(0, 1), (3, 1), (2, 3)];
+        assert!(fits_shape(Some(PowerupKind::Rows), &every_other_row));
+        assert!(!fits_shape(Some(PowerupKind::Columns), &every_other_row));
+        // Rows of both parities can't come from one cursor
+        assert!(!fits_shape(Some(PowerupKind::Rows), &[(0, 1), (0, 2)]));
+    }
+
+    #[test]
+    fn fits_columns() {
+        let every_other_column = [(2, 0), (2, 5), (4, 1)];
+        assert!(fits_shape(Some(PowerupKind::Columns), &every_other_column));
+        assert!(!fits_shape(Some(PowerupKind::Rows), &every_other_column));
+        assert!(!fits_shape(Some(PowerupKind::Columns), &[(1, 0), (2, 0)]));
+    }
+
+    #[test]
+    fn fits_crosses() {
+        // Even columns and odd rows around a cursor at (0, 1)
+        let cross = [(0, 0), (2, 2), (1, 1), (3, 3)];
+        assert!(fits_shape(Some(PowerupKind::RowsColumns), &cross));
+        assert!(!fits_shape(Some(PowerupKind::Rows), &cross));
+        assert!(!fits_shape(Some(PowerupKind::Columns), &cross));
+        // Wherever the cursor is, one of these is in neither its rows nor its columns
+        let every_parity = [(0, 0), (1, 1), (1, 0), (0, 1)];
+        assert!(!fits_shape(Some(PowerupKind::RowsColumns), &every_parity));
+    }
+
+    #[test]
+    fn random_fits_anything() {
+        assert!(fits_shape(
+            Some(PowerupKind::Random),
+            &[(0, 0), (1, 1), (1, 0), (0, 1)]
+        ));
+    }
+}
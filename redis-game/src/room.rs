@@ -0,0 +1,424 @@
+//! Key and channel naming for game rooms
+//!
+//! Every room lives in its own `room:{id}:` namespace so
+//! several games can share the same Redis instance without
+//! seeing each other's players.
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use axum::http::StatusCode;
+use color_eyre::eyre::{self, Context, eyre};
+use redis::{AsyncTypedCommands, aio::ConnectionManager};
+
+use crate::{
+    error::{self, WithStatusCode},
+    metrics::{self, LeaveLabels, METRICS},
+    scripts,
+};
+
+pub const DEFAULT_ROOM: &str = "default";
+/// `SCAN` pattern matching the keys of every room
+pub const ALL_ROOMS_PATTERN: &str = "room:*";
+/// `PSUBSCRIBE` patterns matching the channels of every room
+pub const ALL_ROOM_CHANNELS: [&str; 3] = ["room:*:joins", "room:*:leaves", "room:*:score-updates"];
+const MAX_ROOM_LEN: usize = 64;
+const RESET_BATCH_SIZE: usize = 500;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Room(String);
+
+/// How fast a single player may click, enforced with a token bucket
+#[derive(Clone, Copy, Debug)]
+pub struct ClickLimit {
+    pub per_second: u32,
+    pub burst: u32,
+    /// Cells a single click can hit, a powerup lets one click hit many
+    pub max_cells: u32,
+}
+
+/// What was published on one of a room's channels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    Joins,
+    Leaves,
+    ScoreUpdates,
+}
+
+impl Room {
+    /// Validates a room id taken from a URL path or the command line,
+    /// only ASCII alphanumerics, `-` and `_` are allowed so the id
+    /// can't escape its key namespace.
+    pub fn new(id: impl Into<String>) -> error::Result<Self> {
+        let id = id.into();
+        if id.is_empty() || id.len() > MAX_ROOM_LEN {
+            return Err(eyre!(
+                "Room id must be between 1 and {} characters long",
+                MAX_ROOM_LEN
+            ))
+            .with_status_code(StatusCode::BAD_REQUEST);
+        }
+        if !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(eyre!(
+                "Room id `{}` may only contain ASCII letters, digits, `-` and `_`",
+                id
+            ))
+            .with_status_code(StatusCode::BAD_REQUEST);
+        }
+        Ok(Self(id))
+    }
+
+    /// `SCAN` pattern matching every key in this room
+    pub fn pattern(&self) -> String {
+        format!("room:{}:*", self.0)
+    }
+
+    /// Sorted set of every player in this room, scored by their points
+    pub fn scores_key(&self) -> String {
+        format!("room:{}:scores", self.0)
+    }
+
+    /// Hash of every player's name to their numeric id
+    pub fn player_ids_key(&self) -> String {
+        format!("room:{}:player-ids", self.0)
+    }
+
+    /// Hash of every player's numeric id back to their name
+    pub fn player_names_key(&self) -> String {
+        format!("room:{}:player-names", self.0)
+    }
+
+    /// Hash of every player's name to the session of the connection playing them
+    pub fn sessions_key(&self) -> String {
+        format!("room:{}:sessions", self.0)
+    }
+
+    /// Hash of every player's name to the secret they resume with, which
+    /// unlike their session never shows up in logs or traces
+    pub fn resume_tokens_key(&self) -> String {
+        format!("room:{}:resume-tokens", self.0)
+    }
+
+    /// Sorted set of players whose connection dropped, scored by the
+    /// Unix time in milliseconds until which they can still resume
+    pub fn disconnected_key(&self) -> String {
+        format!("room:{}:disconnected", self.0)
+    }
+
+    /// Token bucket limiting how fast the player with `player_id` can click
+    pub fn click_bucket_key(&self, player_id: u32) -> String {
+        format!("room:{}:click-bucket:{}", self.0, player_id)
+    }
+
+    /// Set while the player with `player_id` has a powerup active, holds its kind
+    pub fn powerup_key(&self, player_id: u32) -> String {
+        format!("room:{}:powerup:{}", self.0, player_id)
+    }
+
+    /// Set until the player with `player_id` can activate their next powerup
+    pub fn powerup_cooldown_key(&self, player_id: u32) -> String {
+        format!("room:{}:powerup-cooldown:{}", self.0, player_id)
+    }
+
+    /// Hash with the phase of the room's round, its number and when the phase ends
+    pub fn round_key(&self) -> String {
+        format!("room:{}:round", self.0)
+    }
+
+    /// List of the best players of the last round and their scores
+    pub fn podium_key(&self) -> String {
+        format!("room:{}:podium", self.0)
+    }
+
+    /// Counter handing out the room's player ids
+    pub fn next_player_id_key(&self) -> String {
+        format!("room:{}:next-player-id", self.0)
+    }
+
+    pub fn joins_channel(&self) -> String {
+        format!("room:{}:joins", self.0)
+    }
+
+    pub fn leaves_channel(&self) -> String {
+        format!("room:{}:leaves", self.0)
+    }
+
+    pub fn score_updates_channel(&self) -> String {
+        format!("room:{}:score-updates", self.0)
+    }
+
+    /// Splits a channel matched by [`ALL_ROOM_CHANNELS`] back into its room and kind
+    pub fn parse_channel(channel: &str) -> Option<(Self, Channel)> {
+        let (id, channel) = channel.strip_prefix("room:")?.rsplit_once(':')?;
+        let channel = match channel {
+            "joins" => Channel::Joins,
+            "leaves" => Channel::Leaves,
+            "score-updates" => Channel::ScoreUpdates,
+            _ => return None,
+        };
+        Some((Self::new(id).ok()?, channel))
+    }
+}
+
+/// Deletes every key matching `pattern`, anything outside of the
+/// game's namespace is left alone. Returns the number of deleted keys.
+pub async fn reset(connection: &mut ConnectionManager, pattern: &str) -> eyre::Result<usize> {
+    let mut keys = Vec::new();
+    {
+        let mut scan: redis::AsyncIter<'_, String> = connection
+            .scan_match(pattern)
+            .await
+            .wrap_err("Failed to open scan on Redis connection")?;
+        while let Some(key) = scan.next_item().await {
+            keys.push(key.wrap_err("Failed to get key from Redis scan")?);
+        }
+    }
+
+    let mut deleted = 0;
+    for batch in keys.chunks(RESET_BATCH_SIZE) {
+        deleted += connection
+            .unlink(batch)
+            .await
+            .wrap_err("Failed to unlink room keys")?;
+    }
+
+    Ok(deleted)
+}
+
+/// Registers `name` in the room with `score` points for `session`, returning
+/// their player id or `None` if another session already took the name.
+/// `resume_token` lets them pick the game back up if their connection drops.
+pub async fn join(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    name: &str,
+    score: i64,
+    session: &str,
+    resume_token: &str,
+) -> redis::RedisResult<Option<u32>> {
+    metrics::timed(
+        "join",
+        scripts::JOIN
+            .key(room.scores_key())
+            .key(room.player_ids_key())
+            .key(room.player_names_key())
+            .key(room.next_player_id_key())
+            .key(room.joins_channel())
+            .key(room.sessions_key())
+            .key(room.resume_tokens_key())
+            .arg(name)
+            .arg(score)
+            .arg(session)
+            .arg(resume_token)
+            .invoke_async(connection),
+    )
+    .await
+}
+
+/// Removes `name` from the room and announces it on the leaves channel,
+/// as long as the name still belongs to `session`
+pub async fn leave(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    name: &str,
+    session: &str,
+) -> redis::RedisResult<()> {
+    metrics::timed(
+        "leave",
+        scripts::LEAVE
+            .key(room.scores_key())
+            .key(room.player_ids_key())
+            .key(room.player_names_key())
+            .key(room.leaves_channel())
+            .key(room.sessions_key())
+            .key(room.resume_tokens_key())
+            .arg(name)
+            .arg(session)
+            .invoke_async(connection),
+    )
+    .await
+}
+
+/// Keeps a player whose connection dropped in the room for `grace_period`,
+/// so they can resume with their resume token
+pub async fn suspend(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    name: &str,
+    session: &str,
+    grace_period: Duration,
+) -> redis::RedisResult<()> {
+    metrics::timed(
+        "suspend",
+        scripts::SUSPEND
+            .key(room.sessions_key())
+            .key(room.disconnected_key())
+            .arg(name)
+            .arg(session)
+            .arg(grace_period.as_millis() as u64)
+            .invoke_async(connection),
+    )
+    .await
+}
+
+/// Hands the player called `name` over to `session` if `resume_token` is
+/// the one they were given, returning their player id. The token is swapped
+/// for `next_resume_token` so an old one can't be used again.
+pub async fn resume(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    name: &str,
+    resume_token: &str,
+    session: &str,
+    next_resume_token: &str,
+) -> redis::RedisResult<Option<u32>> {
+    metrics::timed(
+        "resume",
+        scripts::RESUME
+            .key(room.sessions_key())
+            .key(room.disconnected_key())
+            .key(room.player_ids_key())
+            .key(room.resume_tokens_key())
+            .arg(name)
+            .arg(resume_token)
+            .arg(session)
+            .arg(next_resume_token)
+            .invoke_async(connection),
+    )
+    .await
+}
+
+/// Removes the players whose grace period ran out, returning how many there were
+pub async fn reap(connection: &mut ConnectionManager, room: &Room) -> redis::RedisResult<usize> {
+    let reaped = metrics::timed(
+        "reap",
+        scripts::REAP
+            .key(room.scores_key())
+            .key(room.player_ids_key())
+            .key(room.player_names_key())
+            .key(room.leaves_channel())
+            .key(room.sessions_key())
+            .key(room.disconnected_key())
+            .key(room.resume_tokens_key())
+            .invoke_async(connection),
+    )
+    .await?;
+    METRICS
+        .leaves
+        .get_or_create(&LeaveLabels { reason: "expired" })
+        .inc_by(reaped as u64);
+    Ok(reaped)
+}
+
+/// Takes the token for one click from the player's bucket, returning
+/// whether they are allowed to make it, however many cells it hits
+pub async fn take_click_token(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    player_id: u32,
+    limit: ClickLimit,
+) -> redis::RedisResult<bool> {
+    let taken: u32 = metrics::timed(
+        "take_tokens",
+        scripts::TAKE_TOKENS
+            .key(room.click_bucket_key(player_id))
+            .arg(limit.burst)
+            .arg(limit.per_second)
+            .arg(1)
+            .invoke_async(connection),
+    )
+    .await?;
+    Ok(taken == 1)
+}
+
+/// The current scores of the players with the given ids, skipping anyone who left
+pub async fn scores(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    ids: &[u32],
+) -> redis::RedisResult<Vec<(u32, f64)>> {
+    metrics::timed(
+        "scores",
+        scripts::SCORES
+            .key(room.scores_key())
+            .key(room.player_names_key())
+            .arg(ids)
+            .invoke_async(connection),
+    )
+    .await
+}
+
+/// Adds the points of each click to the score of the player with its id in
+/// one round trip, returning the ids of the clicks that didn't count because
+/// nobody in the room has them or, if the room plays `rounds`, none is being played
+pub async fn click(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    rounds: bool,
+    clicks: &[(u32, i64)],
+) -> redis::RedisResult<Vec<u32>> {
+    let mut invocation = scripts::CLICK.prepare_invoke();
+    invocation
+        .key(room.scores_key())
+        .key(room.player_names_key())
+        .key(room.score_updates_channel())
+        .key(room.round_key())
+        .arg(u8::from(rounds));
+    for (target, points) in clicks {
+        invocation.arg(target).arg(points);
+    }
+    metrics::timed("click", invocation.invoke_async(connection)).await
+}
+
+impl Default for Room {
+    fn default() -> Self {
+        Self(DEFAULT_ROOM.to_owned())
+    }
+}
+
+impl FromStr for Room {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> error::Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl Display for Room {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_room_ids() {
+        assert!(Room::new("team-a_2").is_ok());
+        assert!(Room::new("").is_err());
+        assert!(Room::new("a".repeat(MAX_ROOM_LEN + 1)).is_err());
+        // Would break out of the room's keys
+        assert!(Room::new("a:b").is_err());
+        assert!(Room::new("a*").is_err());
+    }
+
+    #[test]
+    fn parses_channels() {
+        let room = Room::new("team-a").unwrap();
+        for (channel, kind) in [
+            (room.joins_channel(), Channel::Joins),
+            (room.leaves_channel(), Channel::Leaves),
+            (room.score_updates_channel(), Channel::ScoreUpdates),
+        ] {
+            assert_eq!(Room::parse_channel(&channel), Some((room.clone(), kind)));
+        }
+
+        assert_eq!(Room::parse_channel("room:team-a:scores"), None);
+        assert_eq!(Room::parse_channel("other:team-a:joins"), None);
+        assert_eq!(Room::parse_channel("room:joins"), None);
+        assert_eq!(Room::parse_channel("room:a:b:joins"), None);
+    }
+}
@@ -0,0 +1,453 @@
+//! Timed rounds every room plays one after another
+//!
+//! A room waits in the lobby until enough players joined, counts down,
+//! plays for a while and shows the podium before the next round. The phase
+//! and when it ends are kept in Redis, and every replica moves the rooms it
+//! has sockets in along with the same script, so they all agree on both.
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{self, Context, eyre};
+use redis::aio::ConnectionManager;
+
+use crate::{
+    messages::redis_game::{RankedKeyValue, RoundPhase, RoundState},
+    metrics,
+    room::Room,
+    rules::GameRules,
+    scripts,
+};
+
+/// How many of a round's best players make its podium
+const PODIUM_SIZE: u32 = 3;
+
+/// Where a room's round is at, as last read from Redis
+#[derive(Clone, Debug)]
+pub struct Round {
+    pub phase: RoundPhase,
+    pub number: u32,
+    /// When the phase ends, the lobby waits for as long as it takes
+    ends: Option<Instant>,
+    /// How long the phase lasts in all
+    duration: Duration,
+    min_players: u32,
+    /// The round's best players and their scores, kept while showing results
+    podium: Vec<(String, i64)>,
+}
+
+impl Round {
+    /// Whether the round moved on to another phase since `earlier` was read
+    pub fn moved_on_from(&self, earlier: &Self) -> bool {
+        self.phase != earlier.phase || self.number != earlier.number
+    }
+
+    pub fn to_message(&self) -> RoundState<'_> {
+        let lobby = self.phase == RoundPhase::Lobby;
+        RoundState {
+            phase: Some(self.phase),
+            number: Some(self.number),
+            remaining_ms: self
+                .ends
+                .map(|ends| ends.saturating_duration_since(Instant::now()).as_millis() as u32),
+            duration_ms: (!lobby).then_some(self.duration.as_millis() as u32),
+            min_players: lobby.then_some(self.min_players),
+            podium: (self.phase == RoundPhase::Results).then(|| {
+                self.podium
+                    .iter()
+                    .zip(1..)
+                    .map(|((key, value), rank)| RankedKeyValue {
+                        rank,
+                        key,
+                        value: *value,
+                    })
+                    .collect()
+            }),
+        }
+    }
+
+    fn from_reply(
+        (phase, number, remaining_ms, podium): Reply,
+        rules: &GameRules,
+    ) -> eyre::Result<Self> {
+        let (phase, duration) = match phase.as_str() {
+            "lobby" => (RoundPhase::Lobby, Duration::ZERO),
+            "countdown" => (RoundPhase::Countdown, rules.countdown),
+            "playing" => (RoundPhase::Playing, rules.round),
+            "results" => (RoundPhase::Results, rules.results),
+            phase => return Err(eyre!("Unknown round phase `{}`", phase)),
+        };
+        Ok(Self {
+            phase,
+            number,
+            ends: (phase != RoundPhase::Lobby)
+                .then(|| Instant::now() + Duration::from_millis(remaining_ms)),
+            duration,
+            min_players: rules.min_players,
+            podium: podium
+                .into_iter()
+                .map(|(name, score)| (name, score as i64))
+                .collect(),
+        })
+    }
+}
+
+/// The round script's reply: the phase, the round's number, how many
+/// milliseconds are left of the phase and the podium while showing results
+type Reply = (String, u32, u64, Vec<(String, f64)>);
+
+/// Moves the room's round on if its phase is over, returning where it's at
+pub async fn advance(
+    connection: &mut ConnectionManager,
+    room: &Room,
+    rules: &GameRules,
+) -> eyre::Result<Round> {
+    let reply: Reply = metrics::timed(
+        "advance_round",
+        scripts::ADVANCE_ROUND
+            .key(room.round_key())
+            .key(room.scores_key())
+            .key(room.podium_key())
+            .arg(rules.min_players)
+            .arg(rules.countdown.as_millis() as u64)
+            .arg(rules.round.as_millis() as u64)
+            .arg(rules.results.as_millis() as u64)
+            .arg(rules.start_score)
+            .arg(PODIUM_SIZE)
+            .invoke_async(connection),
+    )
+    .await
+    .wrap_err("Failed to advance the round")?;
+    Round::from_reply(reply, rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use mlua::{Lua, Table};
+
+    use super::*;
+
+    /// Just enough of Redis for the round script, with a clock the tests set
+    const FAKE_REDIS: &str = r#"
+        data = {}
+        now = 0
+        redis = {}
+        function redis.call(command, key, ...)
+            local args = {...}
+            if command == 'TIME' then
+                return {tostring(math.floor(now / 1000)), tostring(now % 1000 * 1000)}
+            elseif command == 'HMGET' then
+                local values = {}
+                for i, field in ipairs(args) do
+                    values[i] = (data[key] or {})[field] or false
+                end
+                return values
+            elseif command == 'HGET' then
+                return (data[key] or {})[args[1]] or false
+            elseif command == 'HSET' then
+                data[key] = data[key] or {}
+                for i = 1, #args, 2 do
+                    data[key][args[i]] = tostring(args[i + 1])
+                end
+            elseif command == 'ZCARD' then
+                local len = 0
+                for _ in pairs(data[key] or {}) do
+                    len = len + 1
+                end
+                return len
+            elseif command == 'ZADD' then
+                -- Only `ZADD key XX score member`
+                if data[key][args[3]] then
+                    data[key][args[3]] = tonumber(args[2])
+                end
+            elseif command == 'ZINCRBY' then
+                data[key][args[2]] = data[key][args[2]] + tonumber(args[1])
+                return tostring(data[key][args[2]])
+            elseif command == 'PUBLISH' then
+                return 0
+            elseif command == 'ZRANGE' then
+                -- Only `ZRANGE key start stop [REV] [WITHSCORES]`
+                local members = {}
+                for member, score in pairs(data[key] or {}) do
+                    table.insert(members, {member, score})
+                end
+                local rev = args[3] == 'REV'
+                table.sort(members, function(a, b)
+                    if rev then
+                        return a[2] > b[2]
+                    end
+                    return a[2] < b[2]
+                end)
+                local stop = args[2] < 0 and #members + args[2] or args[2]
+                local reply = {}
+                for i = args[1] + 1, math.min(stop + 1, #members) do
+                    table.insert(reply, members[i][1])
+                    if args[#args] == 'WITHSCORES' then
+                        table.insert(reply, tostring(members[i][2]))
+                    end
+                end
+                return reply
+            elseif command == 'DEL' then
+                data[key] = nil
+            elseif command == 'RPUSH' then
+                data[key] = data[key] or {}
+                for _, value in ipairs(args) do
+                    table.insert(data[key], value)
+                end
+            elseif command == 'LRANGE' then
+                return data[key] or {}
+            else
+                error('Unexpected command ' .. command)
+            end
+        end
+    "#;
+
+    fn rules() -> GameRules {
+        GameRules {
+            click_points: 1,
+            start_score: 100,
+            populate_score: 0,
+            tick: Duration::from_millis(50),
+            min_players: 2,
+            countdown: Duration::from_secs(5),
+            round: Duration::from_secs(60),
+            results: Duration::from_secs(10),
+            powerup: Duration::from_secs(8),
+            powerup_cooldown: Duration::from_secs(10),
+        }
+    }
+
+    /// A room with its round kept by the real script in a fake Redis
+    struct FakeRoom {
+        lua: Lua,
+        rules: GameRules,
+    }
+
+    impl FakeRoom {
+        fn new(players: &[&str]) -> Self {
+            let lua = Lua::new();
+            lua.load(FAKE_REDIS).exec().unwrap();
+            let room = Self {
+                lua,
+                rules: rules(),
+            };
+            for (id, player) in (1..).zip(players) {
+                room.set_score(player, 0);
+                room.names().set(id.to_string(), *player).unwrap();
+            }
+            room
+        }
+
+        /// Every player's name by their id
+        fn names(&self) -> Table<'_> {
+            let data: Table = self.lua.globals().get("data").unwrap();
+            if !data.contains_key("names").unwrap() {
+                data.set("names", self.lua.create_table().unwrap()).unwrap();
+            }
+            data.get("names").unwrap()
+        }
+
+        fn scores(&self) -> Table<'_> {
+            let data: Table = self.lua.globals().get("data").unwrap();
+            if !data.contains_key("scores").unwrap() {
+                data.set("scores", self.lua.create_table().unwrap())
+                    .unwrap();
+            }
+            data.get("scores").unwrap()
+        }
+
+        fn set_score(&self, player: &str, score: i64) {
+            self.scores().set(player, score).unwrap();
+        }
+
+        fn score(&self, player: &str) -> i64 {
+            self.scores().get(player).unwrap()
+        }
+
+        fn leave(&self, player: &str) {
+            self.scores().set(player, mlua::Nil).unwrap();
+        }
+
+        /// Runs the round script at `now` milliseconds, returning the round
+        /// and how many milliseconds of its phase are left
+        fn advance(&self, now: u64) -> (Round, u64) {
+            let globals = self.lua.globals();
+            globals.set("now", now).unwrap();
+            globals.set("KEYS", ["round", "scores", "podium"]).unwrap();
+            let rules = &self.rules;
+            let args = [
+                rules.min_players.to_string(),
+                rules.countdown.as_millis().to_string(),
+                rules.round.as_millis().to_string(),
+                rules.results.as_millis().to_string(),
+                rules.start_score.to_string(),
+                PODIUM_SIZE.to_string(),
+            ];
+            globals.set("ARGV", args).unwrap();
+
+            let reply: Table = self
+                .lua
+                .load(include_str!("scripts/advance_round.lua"))
+                .eval()
+                .unwrap();
+            let remaining: u64 = reply.get(3).unwrap();
+            let podium: Vec<String> = reply.get(4).unwrap();
+            let podium = podium
+                .chunks(2)
+                .map(|pair| (pair[0].clone(), pair[1].parse().unwrap()))
+                .collect();
+            let reply = (
+                reply.get(1).unwrap(),
+                reply.get(2).unwrap(),
+                remaining,
+                podium,
+            );
+            (Round::from_reply(reply, rules).unwrap(), remaining)
+        }
+
+        /// Runs the click script at `now` milliseconds for a click on the
+        /// player with `id`, returning whether it counted
+        fn click(&self, now: u64, id: u32, points: i64) -> bool {
+            let globals = self.lua.globals();
+            globals.set("now", now).unwrap();
+            globals
+                .set("KEYS", ["scores", "names", "score-updates", "round"])
+                .unwrap();
+            let args = [
+                u8::from(self.rules.rounds()).to_string(),
+                id.to_string(),
+                points.to_string(),
+            ];
+            globals.set("ARGV", args).unwrap();
+
+            let missed: Vec<String> = self
+                .lua
+                .load(include_str!("scripts/click.lua"))
+                .eval()
+                .unwrap();
+            missed.is_empty()
+        }
+    }
+
+    const COUNTDOWN: u64 = 5_000;
+    const ROUND: u64 = 60_000;
+    const RESULTS: u64 = 10_000;
+
+    #[test]
+    fn waits_in_the_lobby_for_enough_players() {
+        let room = FakeRoom::new(&["a"]);
+        let (round, _) = room.advance(0);
+        assert_eq!((round.phase, round.number), (RoundPhase::Lobby, 0));
+        let message = round.to_message();
+        assert_eq!(message.min_players, Some(2));
+        assert_eq!(message.remaining_ms, None);
+        assert_eq!(message.duration_ms, None);
+
+        room.set_score("b", 50);
+        let (round, remaining) = room.advance(1_000);
+        assert_eq!((round.phase, round.number), (RoundPhase::Countdown, 1));
+        assert_eq!(remaining, COUNTDOWN);
+        assert_eq!(round.to_message().duration_ms, Some(COUNTDOWN as u32));
+        // Everyone starts the round from the same score
+        assert_eq!(room.score("a"), 100);
+        assert_eq!(room.score("b"), 100);
+    }
+
+    #[test]
+    fn plays_a_round_through() {
+        let room = FakeRoom::new(&["a", "b", "c", "d"]);
+        let (countdown, _) = room.advance(0);
+        assert_eq!(countdown.phase, RoundPhase::Countdown);
+        let (still, remaining) = room.advance(COUNTDOWN - 1);
+        assert!(!still.moved_on_from(&countdown));
+        assert_eq!(remaining, 1);
+
+        let (playing, remaining) = room.advance(COUNTDOWN);
+        assert!(playing.moved_on_from(&countdown));
+        assert_eq!((playing.phase, playing.number), (RoundPhase::Playing, 1));
+        assert_eq!(remaining, ROUND);
+        assert!(playing.to_message().podium.is_none());
+
+        for (player, score) in [("a", 5), ("b", 30), ("c", 20), ("d", 10)] {
+            room.set_score(player, score);
+        }
+        let (results, remaining) = room.advance(COUNTDOWN + ROUND);
+        assert_eq!((results.phase, results.number), (RoundPhase::Results, 1));
+        assert_eq!(remaining, RESULTS);
+        let podium = results
+            .to_message()
+            .podium
+            .unwrap()
+            .into_iter()
+            .map(|ranked| (ranked.rank, ranked.key.to_owned(), ranked.value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            podium,
+            [
+                (1, "b".to_owned(), 30),
+                (2, "c".to_owned(), 20),
+                (3, "d".to_owned(), 10)
+            ]
+        );
+
+        // The lobby goes straight into the next countdown with enough players
+        let (next, remaining) = room.advance(COUNTDOWN + ROUND + RESULTS);
+        assert_eq!((next.phase, next.number), (RoundPhase::Countdown, 2));
+        assert_eq!(remaining, COUNTDOWN);
+        assert_eq!(room.score("b"), 100);
+        assert!(next.to_message().podium.is_none());
+    }
+
+    #[test]
+    fn catches_up_on_missed_phases() {
+        let room = FakeRoom::new(&["a", "b"]);
+        room.advance(0);
+        // Phases end when they were due, not when the script next ran
+        let (round, remaining) = room.advance(COUNTDOWN + ROUND + 1_000);
+        assert_eq!((round.phase, round.number), (RoundPhase::Results, 1));
+        assert_eq!(remaining, RESULTS - 1_000);
+    }
+
+    #[test]
+    fn goes_back_to_the_lobby_when_players_left() {
+        let room = FakeRoom::new(&["a", "b"]);
+        room.advance(0);
+        room.leave("b");
+        // A started round is played out
+        let (round, _) = room.advance(COUNTDOWN);
+        assert_eq!(round.phase, RoundPhase::Playing);
+        let (round, _) = room.advance(COUNTDOWN + ROUND + RESULTS);
+        assert_eq!((round.phase, round.number), (RoundPhase::Lobby, 1));
+    }
+
+    #[test]
+    fn clicks_only_count_while_a_round_is_played() {
+        let room = FakeRoom::new(&["a", "b"]);
+        room.advance(0);
+        assert!(!room.click(1_000, 1, 5));
+        room.advance(COUNTDOWN);
+        assert!(room.click(COUNTDOWN, 1, 5));
+        assert_eq!(room.score("a"), 105);
+        // The round is over even though nothing moved it on yet
+        assert!(!room.click(COUNTDOWN + ROUND, 1, 5));
+        assert_eq!(room.score("a"), 105);
+        let (results, _) = room.advance(COUNTDOWN + ROUND);
+        assert_eq!(results.phase, RoundPhase::Results);
+        assert!(!room.click(COUNTDOWN + ROUND, 2, 5));
+    }
+
+    #[test]
+    fn clicks_always_count_without_rounds() {
+        let mut room = FakeRoom::new(&["a"]);
+        room.rules.round = Duration::ZERO;
+        assert!(room.click(0, 1, 5));
+        assert!(room.click(1_000, 1, -2));
+        assert_eq!(room.score("a"), 3);
+        // Nobody has the id
+        assert!(!room.click(1_000, 2, 5));
+    }
+
+    #[test]
+    fn rejects_unknown_phases() {
+        let reply = ("overtime".to_owned(), 1, 0, Vec::new());
+        assert!(Round::from_reply(reply, &rules()).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+//! The numbers the game is played with
+//!
+//! They're set in the `[game]` section of the configuration and the ones
+//! clients need are sent in their `Welcome`, so a slower or higher stakes
+//! variant of the game doesn't need a new frontend.
+use std::time::Duration;
+
+use crate::messages::redis_game::Rules;
+
+#[derive(Clone, Copy, Debug)]
+pub struct GameRules {
+    /// Points a click adds to or takes from a player, clients only say who they clicked
+    pub click_points: i64,
+    /// Score new players join with and every round starts from
+    pub start_score: i64,
+    /// Score of the players added by `populate`
+    pub populate_score: i64,
+    /// How often every room's score changes are sent out
+    pub tick: Duration,
+    /// Players a room needs before a round starts
+    pub min_players: u32,
+    /// How long the countdown before a round lasts
+    pub countdown: Duration,
+    /// How long a round is played, zero plays one endless game without rounds
+    pub round: Duration,
+    /// How long the podium is shown after a round
+    pub results: Duration,
+    /// How long a powerup stays active
+    pub powerup: Duration,
+    /// How long after a powerup ends until the next one can be activated
+    pub powerup_cooldown: Duration,
+}
+
+impl GameRules {
+    /// Whether rooms play timed rounds, without them clicks count at any
+    /// time and scores are never reset
+    pub fn rounds(self) -> bool {
+        !self.round.is_zero()
+    }
+
+    /// The rules clients need, the rest only matter to the server
+    pub fn to_message(self) -> Rules {
+        Rules {
+            click_points: Some(self.click_points),
+            start_score: Some(self.start_score),
+            tick_ms: Some(self.tick.as_millis() as u32),
+            rounds: Some(self.rounds()),
+            powerup_ms: Some(self.powerup.as_millis() as u32),
+            powerup_cooldown_ms: Some(self.powerup_cooldown.as_millis() as u32),
+        }
+    }
+}
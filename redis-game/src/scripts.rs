@@ -0,0 +1,68 @@
+//! Lua scripts that have to run atomically inside Redis
+use std::sync::LazyLock;
+
+use redis::Script;
+
+/// Adds the clicks of one message to the clicked players' scores and
+/// publishes each new score in the same step, so every socket sees a
+/// player's updates in the order they were applied. The payload is
+/// `{score} {name}`. Returns the ids whose click didn't count because
+/// nobody in the room has them or the room plays rounds and none is being
+/// played.
+pub static CLICK: LazyLock<Script> =
+    LazyLock::new(|| Script::new(include_str!("scripts/click.lua")));
+
+/// Gives a player whose connection dropped until a deadline to come back,
+/// unless they already resumed on another connection.
+pub static SUSPEND: LazyLock<Script> =
+    LazyLock::new(|| Script::new(include_str!("scripts/suspend.lua")));
+
+/// Moves a player over to a new connection if their resume token is the
+/// random secret they were last handed, which is swapped for the new
+/// connection's so it can't be used twice. Returns their id, or nil if the
+/// token is wrong or their grace period ran out and they were reaped.
+pub static RESUME: LazyLock<Script> =
+    LazyLock::new(|| Script::new(include_str!("scripts/resume.lua")));
+
+/// Removes every player whose grace period ran out, announcing each of
+/// them on the leaves channel. Returns how many players were removed.
+pub static REAP: LazyLock<Script> = LazyLock::new(|| Script::new(include_str!("scripts/reap.lua")));
+
+/// Takes up to the requested number of tokens from a player's click
+/// bucket, refilling it for the time since it was last used. Uses the
+/// Redis clock so every replica agrees on the refill. Returns how many
+/// tokens were taken.
+pub static TAKE_TOKENS: LazyLock<Script> =
+    LazyLock::new(|| Script::new(include_str!("scripts/take_tokens.lua")));
+
+/// Grants a player a random powerup unless their last one is still cooling
+/// down or the room plays rounds and none is being played, the kind and
+/// cooldown are kept in keys that expire on their own.
+/// Returns the kind (0 if none was granted), how many milliseconds the
+/// powerup stays active and how many until the next one can be activated.
+pub static ACTIVATE_POWERUP: LazyLock<Script> =
+    LazyLock::new(|| Script::new(include_str!("scripts/activate_powerup.lua")));
+
+/// Looks up the scores of players by their ids, returning a flat list of
+/// `id, score` pairs that skips players who are no longer in the room.
+pub static SCORES: LazyLock<Script> =
+    LazyLock::new(|| Script::new(include_str!("scripts/scores.lua")));
+
+/// Adds a player to the room under a fresh player id and announces them on
+/// the joins channel with the payload `{id} {score} {name}`. Returns their
+/// id, or nil if another session already plays under the name.
+pub static JOIN: LazyLock<Script> = LazyLock::new(|| Script::new(include_str!("scripts/join.lua")));
+
+/// Removes a player from the room and announces them on the leaves channel
+/// with the payload `{id} {name}`, unless the name belongs to another
+/// session by now.
+pub static LEAVE: LazyLock<Script> =
+    LazyLock::new(|| Script::new(include_str!("scripts/leave.lua")));
+
+/// Moves a room's round on to its next phase once the current one is
+/// over, going by the Redis clock so every replica agrees on when that is.
+/// Starting a round resets every score and ending one keeps its podium.
+/// Returns the phase, the round's number, the milliseconds left in the
+/// phase and the podium as `name, score` pairs while showing results.
+pub static ADVANCE_ROUND: LazyLock<Script> =
+    LazyLock::new(|| Script::new(include_str!("scripts/advance_round.lua")));
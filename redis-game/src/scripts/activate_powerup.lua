@@ -0,0 +1,29 @@
+-- KEYS[1]: the player's active powerup key
+-- KEYS[2]: the player's powerup cooldown key
+-- KEYS[3]: the room's round hash
+-- ARGV[1]: how long a powerup stays active in milliseconds
+-- ARGV[2]: how long until the next powerup in milliseconds, counted from activation
+-- ARGV[3]: how many kinds of powerups there are
+-- ARGV[4]: 1 if the room plays rounds, 0 for an endless game
+local time = redis.call('TIME')
+-- With rounds, powerups are only granted while one is being played
+local playing = ARGV[4] == '0'
+if not playing then
+    local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+    local round = redis.call('HMGET', KEYS[3], 'phase', 'ends_at')
+    playing = round[1] == 'playing' and now < (tonumber(round[2]) or 0)
+end
+local cooldown = redis.call('PTTL', KEYS[2])
+if cooldown > 0 or not playing then
+    cooldown = math.max(cooldown, 0)
+    local kind = redis.call('GET', KEYS[1])
+    if kind then
+        return {tonumber(kind), redis.call('PTTL', KEYS[1]), cooldown}
+    end
+    return {0, 0, cooldown}
+end
+-- math.random is seeded the same on every call, the clock's microseconds vary
+local kind = tonumber(time[2]) % tonumber(ARGV[3]) + 1
+redis.call('SET', KEYS[1], kind, 'PX', ARGV[1])
+redis.call('SET', KEYS[2], kind, 'PX', ARGV[2])
+return {kind, tonumber(ARGV[1]), tonumber(ARGV[2])}
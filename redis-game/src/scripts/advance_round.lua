@@ -0,0 +1,62 @@
+-- KEYS[1]: the room's round hash
+-- KEYS[2]: the room's scores sorted set
+-- KEYS[3]: the room's podium list
+-- ARGV[1]: players needed to start a round
+-- ARGV[2]: how long the countdown lasts in milliseconds
+-- ARGV[3]: how long a round is played in milliseconds
+-- ARGV[4]: how long the results are shown in milliseconds
+-- ARGV[5]: score every player starts a round with
+-- ARGV[6]: how many players make the podium
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local round = redis.call('HMGET', KEYS[1], 'phase', 'number', 'ends_at')
+local phase = round[1] or 'lobby'
+local number = tonumber(round[2]) or 0
+local ends_at = tonumber(round[3]) or 0
+local changed = false
+
+-- A room nobody watched for a while can be several phases behind
+while true do
+    if phase == 'lobby' then
+        if redis.call('ZCARD', KEYS[2]) < tonumber(ARGV[1]) then
+            break
+        end
+        phase = 'countdown'
+        number = number + 1
+        ends_at = now + tonumber(ARGV[2])
+        -- Everyone starts the new round from the same score
+        for _, name in ipairs(redis.call('ZRANGE', KEYS[2], 0, -1)) do
+            redis.call('ZADD', KEYS[2], 'XX', ARGV[5], name)
+        end
+        redis.call('DEL', KEYS[3])
+    elseif now < ends_at then
+        break
+    elseif phase == 'countdown' then
+        phase = 'playing'
+        ends_at = ends_at + tonumber(ARGV[3])
+    elseif phase == 'playing' then
+        phase = 'results'
+        ends_at = ends_at + tonumber(ARGV[4])
+        local podium = redis.call('ZRANGE', KEYS[2], 0, tonumber(ARGV[6]) - 1, 'REV', 'WITHSCORES')
+        redis.call('DEL', KEYS[3])
+        if #podium > 0 then
+            redis.call('RPUSH', KEYS[3], unpack(podium))
+        end
+    else
+        phase = 'lobby'
+    end
+    changed = true
+end
+
+if changed then
+    redis.call('HSET', KEYS[1], 'phase', phase, 'number', number, 'ends_at', ends_at)
+end
+local remaining = 0
+if phase ~= 'lobby' then
+    remaining = math.max(ends_at - now, 0)
+end
+local podium = {}
+if phase == 'results' then
+    podium = redis.call('LRANGE', KEYS[3], 0, -1)
+end
+return {phase, number, remaining, podium}
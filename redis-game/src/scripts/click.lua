@@ -0,0 +1,26 @@
+-- KEYS[1]: the room's scores sorted set
+-- KEYS[2]: the room's player names hash
+-- KEYS[3]: the room's score updates channel
+-- KEYS[4]: the room's round hash
+-- ARGV[1]: 1 if the room plays rounds, 0 for an endless game
+-- ARGV[2..]: pairs of the id of a clicked player and the amount to add to their score
+local missed = {}
+-- With rounds, clicks only count while one is being played
+local playing = ARGV[1] == '0'
+if not playing then
+    local time = redis.call('TIME')
+    local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+    local round = redis.call('HMGET', KEYS[4], 'phase', 'ends_at')
+    -- The phase is only moved on by the next check on the round, the round ends on time
+    playing = round[1] == 'playing' and now < (tonumber(round[2]) or 0)
+end
+for i = 2, #ARGV, 2 do
+    local name = playing and redis.call('HGET', KEYS[2], ARGV[i])
+    if name then
+        local score = redis.call('ZINCRBY', KEYS[1], ARGV[i + 1], name)
+        redis.call('PUBLISH', KEYS[3], score .. ' ' .. name)
+    else
+        table.insert(missed, ARGV[i])
+    end
+end
+return missed
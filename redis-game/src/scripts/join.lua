@@ -0,0 +1,22 @@
+-- KEYS[1]: the room's scores sorted set
+-- KEYS[2]: the room's player ids hash
+-- KEYS[3]: the room's player names hash
+-- KEYS[4]: the room's next player id counter
+-- KEYS[5]: the room's joins channel
+-- KEYS[6]: the room's sessions hash
+-- KEYS[7]: the room's resume tokens hash
+-- ARGV[1]: name of the joining player
+-- ARGV[2]: their starting score
+-- ARGV[3]: session of the connection joining
+-- ARGV[4]: the secret the player can resume with
+if redis.call('HSETNX', KEYS[6], ARGV[1], ARGV[3]) == 0 then
+    -- Someone else is already playing under this name
+    return false
+end
+redis.call('HSET', KEYS[7], ARGV[1], ARGV[4])
+local id = redis.call('INCR', KEYS[4])
+redis.call('HSET', KEYS[2], ARGV[1], id)
+redis.call('HSET', KEYS[3], id, ARGV[1])
+redis.call('ZADD', KEYS[1], ARGV[2], ARGV[1])
+redis.call('PUBLISH', KEYS[5], id .. ' ' .. ARGV[2] .. ' ' .. ARGV[1])
+return id
@@ -0,0 +1,21 @@
+-- KEYS[1]: the room's scores sorted set
+-- KEYS[2]: the room's player ids hash
+-- KEYS[3]: the room's player names hash
+-- KEYS[4]: the room's leaves channel
+-- KEYS[5]: the room's sessions hash
+-- KEYS[6]: the room's resume tokens hash
+-- ARGV[1]: name of the leaving player
+-- ARGV[2]: session of the connection leaving
+if redis.call('HGET', KEYS[5], ARGV[1]) ~= ARGV[2] then
+    -- The name belongs to another session now, leave its state alone
+    return
+end
+redis.call('HDEL', KEYS[5], ARGV[1])
+redis.call('HDEL', KEYS[6], ARGV[1])
+local id = redis.call('HGET', KEYS[2], ARGV[1])
+redis.call('ZREM', KEYS[1], ARGV[1])
+if id then
+    redis.call('HDEL', KEYS[2], ARGV[1])
+    redis.call('HDEL', KEYS[3], id)
+    redis.call('PUBLISH', KEYS[4], id .. ' ' .. ARGV[1])
+end
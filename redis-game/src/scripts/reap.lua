@@ -0,0 +1,23 @@
+-- KEYS[1]: the room's scores sorted set
+-- KEYS[2]: the room's player ids hash
+-- KEYS[3]: the room's player names hash
+-- KEYS[4]: the room's leaves channel
+-- KEYS[5]: the room's sessions hash
+-- KEYS[6]: the room's disconnected players sorted set
+-- KEYS[7]: the room's resume tokens hash
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local expired = redis.call('ZRANGE', KEYS[6], '-inf', now, 'BYSCORE')
+for _, name in ipairs(expired) do
+    redis.call('ZREM', KEYS[6], name)
+    redis.call('HDEL', KEYS[5], name)
+    redis.call('HDEL', KEYS[7], name)
+    local id = redis.call('HGET', KEYS[2], name)
+    redis.call('ZREM', KEYS[1], name)
+    if id then
+        redis.call('HDEL', KEYS[2], name)
+        redis.call('HDEL', KEYS[3], id)
+        redis.call('PUBLISH', KEYS[4], id .. ' ' .. name)
+    end
+end
+return #expired
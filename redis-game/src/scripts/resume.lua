@@ -0,0 +1,15 @@
+-- KEYS[1]: the room's sessions hash
+-- KEYS[2]: the room's disconnected players sorted set
+-- KEYS[3]: the room's player ids hash
+-- KEYS[4]: the room's resume tokens hash
+-- ARGV[1]: name of the returning player
+-- ARGV[2]: the resume token they were given
+-- ARGV[3]: session of the new connection
+-- ARGV[4]: the resume token the new connection hands out
+if redis.call('HGET', KEYS[4], ARGV[1]) ~= ARGV[2] then
+    return false
+end
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[3])
+redis.call('HSET', KEYS[4], ARGV[1], ARGV[4])
+redis.call('ZREM', KEYS[2], ARGV[1])
+return tonumber(redis.call('HGET', KEYS[3], ARGV[1]))
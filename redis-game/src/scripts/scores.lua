@@ -0,0 +1,13 @@
+-- KEYS[1]: the room's scores sorted set
+-- KEYS[2]: the room's player names hash
+-- ARGV: ids of the players to look up
+local scores = {}
+for _, id in ipairs(ARGV) do
+    local name = redis.call('HGET', KEYS[2], id)
+    local score = name and redis.call('ZSCORE', KEYS[1], name)
+    if score then
+        table.insert(scores, id)
+        table.insert(scores, score)
+    end
+end
+return scores
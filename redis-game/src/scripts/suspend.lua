@@ -0,0 +1,13 @@
+-- KEYS[1]: the room's sessions hash
+-- KEYS[2]: the room's disconnected players sorted set
+-- ARGV[1]: name of the disconnected player
+-- ARGV[2]: session of the connection that dropped
+-- ARGV[3]: how long the player has to reconnect in milliseconds
+if redis.call('HGET', KEYS[1], ARGV[1]) ~= ARGV[2] then
+    -- They already resumed on another connection
+    return 0
+end
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+redis.call('ZADD', KEYS[2], now + tonumber(ARGV[3]), ARGV[1])
+return 1
@@ -0,0 +1,17 @@
+-- KEYS[1]: the player's token bucket hash
+-- ARGV[1]: how many tokens the bucket holds when full
+-- ARGV[2]: how many tokens are added back every second
+-- ARGV[3]: how many tokens to take
+local capacity = tonumber(ARGV[1])
+local rate = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) + tonumber(time[2]) / 1000000
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'updated')
+local tokens = tonumber(bucket[1]) or capacity
+local updated = tonumber(bucket[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - updated) * rate)
+local taken = math.min(tonumber(ARGV[3]), math.floor(tokens))
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens - taken), 'updated', tostring(now))
+-- A full bucket is the same as no bucket
+redis.call('EXPIRE', KEYS[1], math.ceil(capacity / rate) + 1)
+return taken
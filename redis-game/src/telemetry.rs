@@ -0,0 +1,127 @@
+//! Optional export of `tracing` spans to an OpenTelemetry collector
+//!
+//! Spans are batched on a background thread and sent over OTLP/HTTP,
+//! so a slow collector never holds up the game.
+use color_eyre::eyre::{self, Context};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    trace::{SdkTracer, SdkTracerProvider},
+};
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+const SERVICE_NAME: &str = "redis-game";
+
+/// Flushes the spans that haven't been exported yet when dropped
+pub struct Telemetry(SdkTracerProvider);
+
+/// Sets up exporting spans to the collector at `endpoint`, the base URL
+/// traces are sent to under `/v1/traces`. The returned guard has to be
+/// kept alive for as long as spans should be exported.
+pub fn layer<S>(
+    endpoint: &str,
+    replica: &str,
+) -> eyre::Result<(OpenTelemetryLayer<S, SdkTracer>, Telemetry)>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()
+        .wrap_err_with(|| format!("Failed to build the OTLP exporter for `{}`", endpoint))?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(SERVICE_NAME)
+                .with_attribute(opentelemetry::KeyValue::new(
+                    "service.instance.id",
+                    replica.to_owned(),
+                ))
+                .build(),
+        )
+        .build();
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME));
+    Ok((layer, Telemetry(provider)))
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(e) = self.0.shutdown() {
+            tracing::warn!(?e, "Failed to flush spans to the collector");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+        time::Duration,
+    };
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    /// Answers a single request like a collector would, sending on its path and body
+    fn collector() -> (String, mpsc::Receiver<(String, Vec<u8>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let path = request_line.split(' ').nth(1).unwrap().to_owned();
+            let mut len = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                let header = header.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    len = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; len];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .unwrap();
+            tx.send((path, body)).unwrap();
+        });
+        (endpoint, rx)
+    }
+
+    #[test]
+    fn exports_spans_when_dropped() {
+        let (endpoint, requests) = collector();
+        let (layer, telemetry) = layer(&format!("{endpoint}/"), "replica-1").unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("socket", room = "lobby").in_scope(|| {});
+        });
+        drop(telemetry);
+
+        let (path, body) = requests.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(path, "/v1/traces");
+        let contains = |needle: &[u8]| body.windows(needle.len()).any(|window| window == needle);
+        assert!(contains(b"socket"));
+        assert!(contains(b"lobby"));
+        assert!(contains(SERVICE_NAME.as_bytes()));
+        assert!(contains(b"replica-1"));
+    }
+}